@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS premints
+(
+    id                 TEXT        NOT NULL,
+    kind               TEXT        NOT NULL,
+    version            BIGINT      NOT NULL,
+    signer             TEXT        NOT NULL,
+    chain_id           BIGINT      NOT NULL,
+    collection_address TEXT,
+    token_id           TEXT, -- may be u256, ensure we can store
+    token_uri          TEXT,
+    json               TEXT        NOT NULL,
+    seen_on_chain      BOOLEAN     NOT NULL DEFAULT false,
+    created_at         TIMESTAMPTZ          DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (kind, id)
+)
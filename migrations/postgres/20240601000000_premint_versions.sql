@@ -0,0 +1,13 @@
+-- every accepted version of a premint, kept when the premint itself is updated or pruned
+CREATE TABLE IF NOT EXISTS premint_versions
+(
+    id           TEXT   NOT NULL,
+    kind         TEXT   NOT NULL,
+    version      BIGINT NOT NULL,
+    json         TEXT   NOT NULL,
+    source       TEXT   NOT NULL, -- api, gossip or sync
+    peer_id      TEXT,            -- peer the version was received from, if any
+    rule_results TEXT   NOT NULL,
+    recorded_at  TEXT   NOT NULL, -- rfc3339
+    PRIMARY KEY (kind, id, version)
+)
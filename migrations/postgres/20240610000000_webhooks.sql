@@ -0,0 +1,27 @@
+-- outbound webhooks registered through the admin api, filters are optional
+CREATE TABLE IF NOT EXISTS webhooks
+(
+    id                 TEXT    NOT NULL PRIMARY KEY,
+    url                TEXT    NOT NULL,
+    secret             TEXT    NOT NULL,
+    kind               TEXT,
+    chain_id           BIGINT,
+    collection_address TEXT,
+    creator_address    TEXT,
+    deliveries         BIGINT  NOT NULL DEFAULT 0,
+    failures           BIGINT  NOT NULL DEFAULT 0,
+    dead_letters       BIGINT  NOT NULL DEFAULT 0,
+    created_at         TEXT    NOT NULL -- rfc3339
+);
+
+-- events that couldn't be delivered after all retries
+CREATE TABLE IF NOT EXISTS webhook_dead_letters
+(
+    webhook_id TEXT    NOT NULL,
+    event      TEXT    NOT NULL,
+    error      TEXT    NOT NULL,
+    attempts   BIGINT  NOT NULL,
+    failed_at  TEXT    NOT NULL -- rfc3339
+);
+
+CREATE INDEX IF NOT EXISTS webhook_dead_letters_webhook_id ON webhook_dead_letters (webhook_id);
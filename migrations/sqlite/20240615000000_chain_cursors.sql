@@ -0,0 +1,9 @@
+-- last block checked for mints of each premint type on each chain
+CREATE TABLE IF NOT EXISTS chain_cursors
+(
+    chain_id     INTEGER NOT NULL,
+    kind         TEXT    NOT NULL,
+    block_number INTEGER NOT NULL,
+    updated_at   TEXT    NOT NULL, -- rfc3339
+    PRIMARY KEY (chain_id, kind)
+);
@@ -1,8 +1,12 @@
 use crate::api::routes::APIResponse;
 use crate::api::AppState;
 use crate::controller::ControllerCommands;
+use crate::rules::RulesSummary;
+use crate::rules_config::RulesConfig;
+use crate::webhooks;
+use crate::webhooks::{CreatedWebhook, DeadLetter, NewWebhook, Webhook};
 use axum::body::Body;
-use axum::extract::{Request, State};
+use axum::extract::{Path, Request, State};
 use axum::http::StatusCode;
 use axum::middleware::Next;
 use axum::response::Response;
@@ -86,3 +90,60 @@ pub async fn sync(State(state): State<AppState>) -> StatusCode {
         Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Json(request): Json<NewWebhook>,
+) -> Result<Json<CreatedWebhook>, (StatusCode, String)> {
+    match webhooks::create_webhook(&state.db, request).await {
+        Ok(webhook) => Ok(Json(webhook.into())),
+        Err(e) => Err((StatusCode::BAD_REQUEST, e.to_string())),
+    }
+}
+
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Webhook>>, (StatusCode, String)> {
+    match webhooks::list_webhooks(&state.db).await {
+        Ok(webhooks) => Ok(Json(webhooks)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub async fn delete_webhook(State(state): State<AppState>, Path(id): Path<String>) -> StatusCode {
+    match webhooks::delete_webhook(&state.db, &id).await {
+        Ok(true) => StatusCode::OK,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub async fn list_dead_letters(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<DeadLetter>>, (StatusCode, String)> {
+    match webhooks::list_dead_letters(&state.db, &id).await {
+        Ok(dead_letters) => Ok(Json(dead_letters)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+pub async fn get_rules(
+    State(state): State<AppState>,
+) -> Result<Json<RulesSummary>, (StatusCode, String)> {
+    match state.controller.get_rules().await {
+        Ok(rules) => Ok(Json(rules)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// Replaces the rules config of the running node, takes effect for the next premint evaluated
+pub async fn update_rules(
+    State(state): State<AppState>,
+    Json(config): Json<RulesConfig>,
+) -> Result<Json<RulesSummary>, (StatusCode, String)> {
+    match state.controller.update_rules(config).await {
+        Ok(rules) => Ok(Json(rules)),
+        Err(e) => Err((StatusCode::BAD_REQUEST, e.to_string())),
+    }
+}
@@ -1,6 +1,7 @@
 // pub mod admin;
 pub mod admin;
 pub mod routes;
+pub mod stream;
 
 use crate::config::Config;
 use crate::controller::{ControllerCommands, ControllerInterface, DBQuery};
@@ -9,9 +10,9 @@ use axum::extract::Request;
 use axum::http::StatusCode;
 use axum::middleware::{from_fn, from_fn_with_state, Next};
 use axum::response::Response;
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::Router;
-use sqlx::SqlitePool;
+use sqlx::AnyPool;
 use std::time::{Duration, SystemTime};
 use tokio::net::TcpListener;
 use tower::buffer::BufferLayer;
@@ -22,7 +23,7 @@ const NO_METRICS_ENDPOINTS: [&str; 2] = ["/health", "/metrics"];
 
 #[derive(Clone)]
 pub struct AppState {
-    pub db: SqlitePool,
+    pub db: AnyPool,
     pub controller: ControllerInterface,
     pub api_secret: Option<String>,
 }
@@ -50,8 +51,12 @@ pub fn router_with_defaults(_config: &Config) -> Router<AppState> {
         .route("/list-all", get(routes::list_all))
         .route("/get-one", get(routes::get_one))
         .route("/get-one/:kind/:id", get(routes::get_by_id_and_kind))
+        .route("/history/:kind/:id", get(routes::get_premint_history))
         .route("/submit-premint", post(routes::submit_premint))
+        .route("/validate-premint", post(routes::validate_premint))
         .route("/summary", get(routes::summary))
+        .route("/events", get(stream::events))
+        .route("/ws", get(stream::ws))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(|error: BoxError| async move {
@@ -85,6 +90,19 @@ pub fn with_admin_routes(state: AppState, router: Router<AppState>) -> Router<Ap
         // admin submit premint route is not rate limited (allows for operator to send high volume of premints)
         .route("/admin/submit-premint", post(routes::submit_premint))
         .route("/admin/sync", post(admin::sync))
+        .route(
+            "/admin/rules",
+            get(admin::get_rules).put(admin::update_rules),
+        )
+        .route(
+            "/admin/webhooks",
+            get(admin::list_webhooks).post(admin::create_webhook),
+        )
+        .route("/admin/webhooks/:id", delete(admin::delete_webhook))
+        .route(
+            "/admin/webhooks/:id/dead-letters",
+            get(admin::list_dead_letters),
+        )
         .layer(from_fn_with_state(state, admin::auth_middleware))
         .layer(
             ServiceBuilder::new()
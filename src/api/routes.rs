@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::Json;
@@ -7,19 +9,32 @@ use serde::Serialize;
 use sqlx::{Executor, Row};
 
 use crate::api::AppState;
+use crate::chain_list::{EndpointStatus, CHAINS};
 use crate::controller::ControllerCommands;
 use crate::p2p::NetworkState;
 use crate::rules::Results;
 use crate::storage;
-use crate::storage::{get_for_id_and_kind, QueryOptions};
+use crate::storage::{get_for_id_and_kind, get_history, PremintPage, PremintVersion, QueryOptions};
+use crate::sync::{PeerSyncStats, TreeRoot};
 use crate::types::{PremintName, PremintTypes};
 
+/// Plain list for unpaged queries, so existing clients keep working.
+/// Queries with a `limit` or `cursor` get a page with the cursor for the next one.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ListResponse {
+    All(Vec<PremintTypes>),
+    Page(PremintPage),
+}
+
 pub async fn list_all(
     State(state): State<AppState>,
     Query(params): Query<QueryOptions>,
-) -> Result<Json<Vec<PremintTypes>>, (StatusCode, String)> {
-    match storage::list_all_with_options(&state.db, &params).await {
-        Ok(premints) => Ok(Json(premints)),
+) -> Result<Json<ListResponse>, (StatusCode, String)> {
+    let paged = params.limit.is_some() || params.cursor.is_some();
+    match storage::list_page_with_options(&state.db, &params).await {
+        Ok(page) if paged => Ok(Json(ListResponse::Page(page))),
+        Ok(page) => Ok(Json(ListResponse::All(page.premints))),
         Err(_e) => {
             tracing::warn!("Failed to list all premints: {:?}", _e);
 
@@ -58,6 +73,19 @@ pub async fn get_by_id_and_kind(
     }
 }
 
+pub async fn get_premint_history(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(String, String)>,
+) -> Result<Json<Vec<PremintVersion>>, (StatusCode, String)> {
+    match get_history(&state.db, &id, PremintName(kind)).await {
+        Ok(history) if history.is_empty() => {
+            Err((StatusCode::NOT_FOUND, "No history for premint".to_string()))
+        }
+        Ok(history) => Ok(Json(history)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
 pub async fn health() -> &'static str {
     "OK"
 }
@@ -116,6 +144,29 @@ pub async fn submit_premint(
     }
 }
 
+#[derive(Serialize)]
+pub struct ValidationResponse {
+    pub accepted: bool,
+    pub results: Results,
+}
+
+/// Runs the rules on a premint like `/submit-premint` does, without storing or broadcasting it
+pub async fn validate_premint(
+    State(state): State<AppState>,
+    Json(premint): Json<PremintTypes>,
+) -> Result<Json<ValidationResponse>, (StatusCode, String)> {
+    match state.controller.validate_premint(premint).await {
+        Ok(results) => Ok(Json(ValidationResponse {
+            accepted: results.is_accept(),
+            results,
+        })),
+        Err(e) => {
+            tracing::warn!("Failed to validate premint: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub enum APIResponse {
     RulesError { evaluation: Results },
@@ -144,6 +195,27 @@ pub async fn summary(State(state): State<AppState>) -> Result<Json<SummaryRespon
                     .await
                     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
                     .get::<i64, _>("count");
+                let premint_tree_roots = state.controller.get_tree_roots().await.map_err(|e| {
+                    tracing::error!(
+                        error = e.to_string(),
+                        "Error fetching premint tree roots for /summary"
+                    );
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+                let peer_sync_stats = state
+                    .controller
+                    .get_sync_stats()
+                    .await
+                    .map_err(|e| {
+                        tracing::error!(
+                            error = e.to_string(),
+                            "Error fetching peer sync stats for /summary"
+                        );
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    })?
+                    .into_iter()
+                    .map(|(peer_id, stats)| (peer_id.to_string(), stats))
+                    .collect();
 
                 Ok(Json(SummaryResponse {
                     commit_sha: crate::built_info::GIT_COMMIT_HASH_SHORT
@@ -152,6 +224,9 @@ pub async fn summary(State(state): State<AppState>) -> Result<Json<SummaryRespon
                     pkg_version: crate::built_info::PKG_VERSION.to_string(),
                     active_premint_count: active as u64,
                     total_premint_count: total as u64,
+                    premint_tree_roots,
+                    peer_sync_stats,
+                    rpc_endpoints: CHAINS.endpoint_status(),
                     node_info: info.into(),
                 }))
             }
@@ -179,6 +254,9 @@ pub struct SummaryResponse {
     pub pkg_version: String,
     pub active_premint_count: u64,
     pub total_premint_count: u64,
+    pub premint_tree_roots: Vec<TreeRoot>,
+    pub peer_sync_stats: HashMap<String, PeerSyncStats>,
+    pub rpc_endpoints: HashMap<u64, Vec<EndpointStatus>>,
     pub node_info: NodeInfoResponse,
 }
 
@@ -0,0 +1,128 @@
+use std::convert::Infallible;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use futures::{Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api::AppState;
+use crate::events::PremintEvent;
+use crate::storage::QueryOptions;
+
+enum StreamItem {
+    Event(PremintEvent),
+    // subscriber fell behind and missed this many events
+    Lagged(u64),
+}
+
+impl StreamItem {
+    fn to_json(&self) -> serde_json::Result<String> {
+        match self {
+            StreamItem::Event(event) => serde_json::to_string(event),
+            StreamItem::Lagged(missed) => {
+                serde_json::to_string(&serde_json::json!({"type": "lagged", "missed": missed}))
+            }
+        }
+    }
+}
+
+fn event_stream(
+    receiver: broadcast::Receiver<PremintEvent>,
+    filter: QueryOptions,
+) -> impl Stream<Item = StreamItem> {
+    futures::stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.matches(&filter) => {
+                    return Some((StreamItem::Event(event), (receiver, filter)))
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::info!(counter.event_stream_lagged = 1);
+                    return Some((StreamItem::Lagged(missed), (receiver, filter)));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+async fn subscribe(
+    state: &AppState,
+) -> Result<broadcast::Receiver<PremintEvent>, (StatusCode, String)> {
+    state.controller.subscribe_events().await.map_err(|e| {
+        tracing::warn!("Failed to subscribe to events: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to subscribe to events".to_string(),
+        )
+    })
+}
+
+/// Server-Sent Events stream of premint events matching the query filters
+pub async fn events(
+    State(state): State<AppState>,
+    Query(filter): Query<QueryOptions>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let receiver = subscribe(&state).await?;
+
+    let stream = event_stream(receiver, filter).filter_map(|item| async move {
+        let name = match &item {
+            StreamItem::Event(event) => event.name(),
+            StreamItem::Lagged(_) => "lagged",
+        };
+        match item.to_json() {
+            Ok(json) => Some(Ok(Event::default().event(name).data(json))),
+            Err(e) => {
+                tracing::warn!("Failed to serialize premint event: {:?}", e);
+                None
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// WebSocket stream of premint events matching the query filters, one JSON message per event
+pub async fn ws(
+    State(state): State<AppState>,
+    Query(filter): Query<QueryOptions>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, (StatusCode, String)> {
+    let receiver = subscribe(&state).await?;
+
+    Ok(upgrade.on_upgrade(move |socket| send_events(socket, event_stream(receiver, filter))))
+}
+
+async fn send_events(mut socket: WebSocket, events: impl Stream<Item = StreamItem>) {
+    let mut events = Box::pin(events);
+
+    loop {
+        tokio::select! {
+            item = events.next() => {
+                let Some(item) = item else { break };
+                let json = match item.to_json() {
+                    Ok(json) => json,
+                    Err(e) => {
+                        tracing::warn!("Failed to serialize premint event: {:?}", e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(json)).await.is_err() {
+                    break;
+                }
+            }
+            // clients don't send anything, this is only to notice when they go away
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
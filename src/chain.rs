@@ -1,15 +1,22 @@
-use crate::chain_list::{ChainListProvider, CHAINS};
+use crate::chain_list::{ChainListProvider, RpcProvider, CHAINS};
 use crate::controller::{ControllerCommands, ControllerInterface};
-use crate::types::{InclusionClaim, Premint, PremintTypes};
-use alloy::primitives::{address, Address, Bytes, TxKind};
+use crate::premints::zora_premint::erc20v1::ERC20V1;
+use crate::premints::zora_premint::v2::V2;
+use crate::storage::{get_chain_cursor, set_chain_cursor};
+use crate::types::{ClaimEvent, InclusionClaim, Premint, PremintName, PremintTypes};
+use crate::view_cache::{ViewCallKey, LATEST, VIEW_CACHE};
+use alloy::primitives::{address, Address, Bytes, B256};
 use alloy::providers::Provider;
-use alloy::rpc::types::eth::{BlockId, Filter, TransactionInput, TransactionRequest};
+use alloy::rpc::types::eth::{Filter, Log};
 use alloy::sol;
 use alloy::sol_types::{SolCall, SolEvent};
 use futures_util::StreamExt;
+use sqlx::AnyPool;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
-/// Helper function for calling view functions for SolCall types
+/// Helper function for calling view functions for SolCall types, batched through multicall
 pub async fn view_contract_call<T>(
     call: T,
     provider: &Arc<ChainListProvider>,
@@ -19,49 +26,242 @@ where
     T: SolCall,
 {
     provider
-        .call(
-            &TransactionRequest {
-                to: Some(TxKind::Call(address)),
-                input: TransactionInput::new(Bytes::from(call.abi_encode())),
-                ..Default::default()
-            },
-            BlockId::latest(),
-        )
+        .view_call(address, Bytes::from(call.abi_encode()))
         .await
         .map_err(|err| eyre::eyre!("Error calling contract: {:?}", err))
-        .and_then(|response| {
-            T::abi_decode_returns(&response, false)
-                .map_err(|err| eyre::eyre!("Error decoding contract response: {:?}", err))
+        .and_then(|response| decode_view_response::<T>(&response))
+}
+
+/// Like `view_contract_call`, for calls whose result rarely changes.
+/// Results are cached for a few minutes, or until the chain watcher sees an event about
+/// the contract or one of the call's arguments.
+pub async fn cached_view_contract_call<T>(
+    call: T,
+    provider: &Arc<ChainListProvider>,
+    address: Address,
+) -> eyre::Result<T::Return>
+where
+    T: SolCall,
+{
+    let key = ViewCallKey {
+        chain_id: provider.chain_id(),
+        contract: address,
+        calldata: Bytes::from(call.abi_encode()),
+        block_tag: LATEST,
+    };
+
+    let response = match VIEW_CACHE.get(&key) {
+        Some(response) => response,
+        None => {
+            let response = provider
+                .view_call(address, key.calldata.clone())
+                .await
+                .map_err(|err| eyre::eyre!("Error calling contract: {:?}", err))?;
+            VIEW_CACHE.insert(key, response.clone());
+            response
+        }
+    };
+
+    decode_view_response::<T>(&response)
+}
+
+fn decode_view_response<T: SolCall>(response: &Bytes) -> eyre::Result<T::Return> {
+    T::abi_decode_returns(response, false)
+        .map_err(|err| eyre::eyre!("Error decoding contract response: {:?}", err))
+}
+
+/// Routes logs of a premint type's claim event to that type's claim mapping
+#[derive(Clone)]
+pub struct ClaimRoute {
+    pub kind: PremintName,
+    pub event: ClaimEvent,
+    map_claim: fn(u64, Log) -> eyre::Result<InclusionClaim>,
+}
+
+impl ClaimRoute {
+    fn of<T: Premint>(kind: &PremintName, chain_id: u64) -> Option<Self> {
+        T::claim_event(chain_id).map(|event| Self {
+            kind: kind.clone(),
+            event,
+            map_claim: T::map_claim,
+        })
+    }
+
+    pub fn matches(&self, log: &Log) -> bool {
+        log.address() == self.event.address && log.topics().first() == Some(&self.event.signature)
+    }
+
+    pub fn map_claim(&self, chain_id: u64, log: Log) -> eyre::Result<InclusionClaim> {
+        (self.map_claim)(chain_id, log)
+    }
+}
+
+/// Claim routes for the given premint types that can be brought onchain on a chain
+pub fn claim_routes(kinds: &[PremintName], chain_id: u64) -> Vec<ClaimRoute> {
+    kinds
+        .iter()
+        .filter_map(|kind| match kind.0.as_str() {
+            "zora_premint_v2" => ClaimRoute::of::<V2>(kind, chain_id),
+            "zora_premint_erc20v1" => ClaimRoute::of::<ERC20V1>(kind, chain_id),
+            _ => None,
         })
+        .collect()
+}
+
+/// Single filter covering the claim events of every route
+pub fn combined_filter(routes: &[ClaimRoute]) -> Filter {
+    let mut addresses: Vec<Address> = routes.iter().map(|r| r.event.address).collect();
+    addresses.sort();
+    addresses.dedup();
+
+    let mut signatures: Vec<B256> = routes.iter().map(|r| r.event.signature).collect();
+    signatures.sort();
+    signatures.dedup();
+
+    Filter::new().address(addresses).event_signature(signatures)
+}
+
+/// How often pending claims are checked against the chain head and the cursor is stored
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+/// How often RPCs without subscriptions are polled for new logs
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Most blocks asked for in a single eth_getLogs, public RPCs reject large ranges
+const LOG_POLL_BLOCK_RANGE: u64 = 1000;
+
+/// Claims of a log that still has to reach the confirmation depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingClaim {
+    pub tx_hash: B256,
+    pub log_index: u64,
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub claims: Vec<InclusionClaim>,
+}
+
+/// Claims waiting for enough blocks on top of them to be safe from reorgs
+pub struct PendingClaims {
+    confirmations: u64,
+    pending: HashMap<(B256, u64), PendingClaim>,
+}
+
+impl PendingClaims {
+    pub fn new(confirmations: u64) -> Self {
+        Self {
+            confirmations,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Cursor that can be stored after processing up to a block. Pending claims only live in
+    /// memory, so it's kept behind the oldest one for its log to be seen again after a restart.
+    pub fn cursor_for(&self, block_number: u64) -> u64 {
+        self.pending
+            .values()
+            .map(|c| c.block_number.saturating_sub(1))
+            .fold(block_number, u64::min)
+    }
+
+    pub fn add(&mut self, claim: PendingClaim) {
+        self.pending.insert((claim.tx_hash, claim.log_index), claim);
+    }
+
+    /// Drops the claims of a log, returns false if they weren't pending
+    pub fn remove(&mut self, tx_hash: B256, log_index: u64) -> bool {
+        self.pending.remove(&(tx_hash, log_index)).is_some()
+    }
+
+    /// Takes out every claim that reached the confirmation depth at the given head
+    pub fn confirmed_at(&mut self, head: u64) -> Vec<PendingClaim> {
+        let confirmations = self.confirmations;
+        let (confirmed, pending): (HashMap<_, _>, HashMap<_, _>) = self
+            .pending
+            .drain()
+            .partition(|(_, claim)| claim.block_number + confirmations <= head);
+        self.pending = pending;
+        confirmed.into_values().collect()
+    }
+}
+
+/// How far a log subscription has delivered. Logs only tell for blocks that have matching ones,
+/// new heads from the same connection cover the blocks in between.
+#[derive(Debug)]
+pub struct SubscriptionProgress {
+    handled: u64,
+    head: u64,
+    // head as of the previous checkpoint
+    settled_head: u64,
+}
+
+impl SubscriptionProgress {
+    pub fn new(handled: u64) -> Self {
+        Self {
+            handled,
+            head: handled,
+            settled_head: handled,
+        }
+    }
+
+    pub fn log(&mut self, block_number: u64) {
+        // more logs of the same block may still be on their way
+        self.handled = self.handled.max(block_number.saturating_sub(1));
+    }
+
+    pub fn head(&mut self, block_number: u64) {
+        self.head = self.head.max(block_number);
+    }
+
+    /// Last block whose logs were all handled. Subscriptions aren't ordered among each other, so
+    /// a head only counts once the next checkpoint gave its logs time to arrive.
+    pub fn checkpoint(&mut self) -> u64 {
+        self.handled = self.handled.max(self.settled_head);
+        self.settled_head = self.head;
+        self.handled
+    }
 }
 
 /// Checks for new premints being brought onchain then sends to controller to handle
 pub struct MintChecker {
     chain_id: u64,
     controller: ControllerInterface,
-    rpc_url: String,
+    routes: Vec<ClaimRoute>,
+    confirmations: u64,
 }
 
 impl MintChecker {
-    pub fn new(chain_id: u64, rpc_url: String, controller: ControllerInterface) -> Self {
+    pub fn new(
+        chain_id: u64,
+        controller: ControllerInterface,
+        routes: Vec<ClaimRoute>,
+        confirmations: u64,
+    ) -> Self {
         Self {
             chain_id,
             controller,
-            rpc_url, // needed in case of WS disconnect so mintchecker can force a reconnect
+            routes,
+            confirmations,
         }
     }
 
-    /// Polls for new mints based on a filter defined by the PremintType
-    pub async fn poll_for_new_mints<T: Premint>(&self) -> eyre::Result<MintCheckerResult> {
-        let mut highest_block: Option<u64> = None;
+    /// Polls for new mints of every routed premint type with a single log subscription, or
+    /// eth_getLogs polling when none of the chain's RPCs are websockets.
+    /// The last processed block is stored, and blocks missed while the node was down or
+    /// disconnected are backfilled before following the chain again.
+    /// Claims are only resolved once their block is `confirmations` deep, logs removed by a reorg
+    /// drop pending claims or revert ones that were already resolved.
+    pub async fn poll_for_new_mints(&self) -> eyre::Result<MintCheckerResult> {
+        let mut pending = PendingClaims::new(self.confirmations);
 
-        let mut filter = if let Some(filter) = T::check_filter(self.chain_id) {
-            filter
-        } else {
-            let err = eyre::eyre!("No filter for chain / premint type, skipping spawning checker");
+        if self.routes.is_empty() {
+            let err = eyre::eyre!("No premint types to check on chain, skipping spawning checker");
             tracing::warn!(error = err.to_string(), "checking failed");
             return Ok(MintCheckerResult::NoFilter);
-        };
+        }
+        let filter = combined_filter(&self.routes);
+        let db = self.controller.get_db().await?;
 
         loop {
             let rpc = match self.make_provider().await {
@@ -72,17 +272,19 @@ impl MintChecker {
                     continue;
                 }
             };
-            tracing::info!(
-                "Starting checker for chain {}, {}",
-                self.chain_id,
-                self.rpc_url
-            );
+            tracing::info!("Starting checker for chain {}", self.chain_id);
 
-            // set start block in case of WS disconnect
-            if let Some(highest_block) = highest_block {
-                filter = filter.from_block(highest_block);
-            }
-            let mut stream = match rpc.subscribe_logs(&filter).await {
+            // the subscription stays on one websocket endpoint, requests go through the pool
+            let ws = rpc.pubsub().await;
+            let Some(pubsub) = ws.as_deref().and_then(RpcProvider::pubsub) else {
+                if let Err(e) = self.poll_logs(&rpc, &db, &filter, &mut pending).await {
+                    tracing::error!("Error polling logs: {}", e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                }
+                continue;
+            };
+
+            let mut stream = match pubsub.subscribe_logs(&filter).await {
                 Ok(t) => t.into_stream(),
                 Err(e) => {
                     tracing::error!("Error subscribing to logs: {}", e);
@@ -91,30 +293,284 @@ impl MintChecker {
                 }
             };
 
-            while let Some(log) = stream.next().await {
-                tracing::debug!("Saw log");
-                match T::map_claim(self.chain_id, log.clone()) {
-                    Ok(claim) => {
-                        tracing::debug!("Found claim of inclusion {:?}", claim);
-                        if let Err(err) = self
-                            .controller
-                            .send_command(ControllerCommands::ResolveOnchainMint(claim))
-                            .await
-                        {
-                            tracing::error!("Error sending claim to controller: {}", err);
+            // heads keep the cursor moving while no premints are minted
+            let mut heads = match pubsub.subscribe_blocks().await {
+                Ok(t) => t.into_stream(),
+                Err(e) => {
+                    tracing::error!("Error subscribing to new heads: {}", e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            // subscribed first so nothing falls between the backfill and the live logs
+            let mut progress = match self.backfill(&rpc, &db, &filter, &mut pending).await {
+                Ok(handled) => SubscriptionProgress::new(handled),
+                Err(e) => {
+                    tracing::error!("Error backfilling logs: {}", e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            let mut checkpoint_interval = tokio::time::interval(CHECKPOINT_INTERVAL);
+            loop {
+                tokio::select! {
+                    log = stream.next() => {
+                        let Some(log) = log else { break };
+                        tracing::debug!("Saw log");
+                        self.handle_log(&log, &mut pending).await;
+                        if let Some(block_number) = log.block_number {
+                            progress.log(block_number);
                         }
                     }
-                    Err(e) => {
-                        tracing::error!("Error processing log while checking premint: {}", e);
+                    block = heads.next() => {
+                        let Some(block) = block else { break };
+                        if let Some(block_number) = block.header.number {
+                            progress.head(block_number);
+                        }
+                    }
+                    _ = checkpoint_interval.tick() => {
+                        self.checkpoint(&rpc, &db, progress.checkpoint(), &mut pending).await;
                     }
                 }
-                if let Some(block_number) = log.block_number {
-                    highest_block = Some(block_number);
+            }
+        }
+    }
+
+    // confirms pending claims and moves the cursor up to the last block handled. That's only as
+    // far as the subscriptions have delivered, so blocks of a stalled subscription are backfilled
+    async fn checkpoint(
+        &self,
+        rpc: &Arc<ChainListProvider>,
+        db: &AnyPool,
+        handled: u64,
+        pending: &mut PendingClaims,
+    ) {
+        match rpc.get_block_number().await {
+            Ok(head) => self.confirm_pending(rpc, head, pending).await,
+            Err(e) => tracing::error!("Error getting block number: {}", e),
+        }
+
+        if let Err(e) = self.save_cursor(db, handled, pending).await {
+            tracing::error!("Error saving chain cursor: {}", e);
+        }
+    }
+
+    /// Catches up from the stored cursor to the current head and returns the last block handled.
+    /// Nothing to fetch on a first start, later logs come from the subscription.
+    async fn backfill(
+        &self,
+        rpc: &Arc<ChainListProvider>,
+        db: &AnyPool,
+        filter: &Filter,
+        pending: &mut PendingClaims,
+    ) -> eyre::Result<u64> {
+        let head = rpc.get_block_number().await?;
+        let Some(cursor) = self.stored_cursor(db).await? else {
+            return Ok(head);
+        };
+        if cursor >= head {
+            return Ok(cursor);
+        }
+
+        tracing::info!(
+            histogram.chain_backfill_blocks = head - cursor,
+            chain_id = self.chain_id,
+            "Backfilling blocks missed since block {}",
+            cursor
+        );
+        self.fetch_logs(rpc, db, filter, cursor + 1, head, pending)
+            .await?;
+        Ok(head)
+    }
+
+    /// Polls eth_getLogs, resuming from the stored cursor so blocks aren't missed between polls
+    /// or across restarts
+    async fn poll_logs(
+        &self,
+        rpc: &Arc<ChainListProvider>,
+        db: &AnyPool,
+        filter: &Filter,
+        pending: &mut PendingClaims,
+    ) -> eyre::Result<()> {
+        let mut from = match self.stored_cursor(db).await? {
+            Some(cursor) => cursor + 1,
+            None => rpc.get_block_number().await?,
+        };
+
+        loop {
+            let head = rpc.get_block_number().await?;
+            if from <= head {
+                self.fetch_logs(rpc, db, filter, from, head, pending)
+                    .await?;
+                from = head + 1;
+            }
+
+            self.confirm_pending(rpc, head, pending).await;
+            tokio::time::sleep(LOG_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Handles the logs of a block range with eth_getLogs in bounded chunks, storing the cursor
+    /// after each chunk
+    async fn fetch_logs(
+        &self,
+        rpc: &Arc<ChainListProvider>,
+        db: &AnyPool,
+        filter: &Filter,
+        from: u64,
+        to: u64,
+        pending: &mut PendingClaims,
+    ) -> eyre::Result<()> {
+        let mut from = from;
+        while from <= to {
+            let chunk_end = to.min(from + LOG_POLL_BLOCK_RANGE - 1);
+            let logs = rpc
+                .get_logs(&filter.clone().from_block(from).to_block(chunk_end))
+                .await?;
+            tracing::info!(
+                histogram.chain_logs_polled = logs.len() as u64,
+                chain_id = self.chain_id
+            );
+
+            for log in logs {
+                self.handle_log(&log, pending).await;
+            }
+
+            self.save_cursor(db, chunk_end, pending).await?;
+            from = chunk_end + 1;
+        }
+        Ok(())
+    }
+
+    // types are checked together, so resume from the one that is furthest behind
+    async fn stored_cursor(&self, db: &AnyPool) -> eyre::Result<Option<u64>> {
+        let mut cursor: Option<u64> = None;
+        for route in &self.routes {
+            if let Some(block) = get_chain_cursor(db, self.chain_id, &route.kind).await? {
+                cursor = Some(cursor.map_or(block, |c| c.min(block)));
+            }
+        }
+        Ok(cursor)
+    }
+
+    async fn save_cursor(
+        &self,
+        db: &AnyPool,
+        block_number: u64,
+        pending: &PendingClaims,
+    ) -> eyre::Result<()> {
+        let block_number = pending.cursor_for(block_number);
+
+        for route in &self.routes {
+            set_chain_cursor(db, self.chain_id, &route.kind, block_number).await?;
+        }
+        Ok(())
+    }
+
+    fn map_claims(&self, log: &Log) -> Vec<InclusionClaim> {
+        // types can share a claim event, so a log is offered to every route it matches
+        self.routes
+            .iter()
+            .filter(|r| r.matches(log))
+            .filter_map(|route| match route.map_claim(self.chain_id, log.clone()) {
+                Ok(claim) => Some(claim),
+                Err(e) => {
+                    tracing::error!(
+                        kind = route.kind.0.as_str(),
+                        "Error processing log while checking premint: {}",
+                        e
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    async fn handle_log(&self, log: &Log, pending: &mut PendingClaims) {
+        let claims = self.map_claims(log);
+        if claims.is_empty() {
+            return;
+        }
+        // supported versions of the collection may have changed
+        VIEW_CACHE.invalidate(self.chain_id, log.topics().get(1..).unwrap_or_default());
+        let tx_hash = log.transaction_hash.unwrap_or_default();
+        let log_index = log.log_index.unwrap_or_default();
+
+        if log.removed {
+            if pending.remove(tx_hash, log_index) {
+                tracing::info!(
+                    counter.pending_claims_reorged = 1,
+                    "Dropped reorged pending claim"
+                );
+            } else {
+                tracing::info!(counter.claims_reverted = 1, "Reverting reorged claim");
+                for claim in claims {
+                    self.send_claim(ControllerCommands::RevertOnchainMint(claim))
+                        .await;
+                }
+            }
+            return;
+        }
+
+        match (self.confirmations, log.block_number, log.block_hash) {
+            (0, _, _) => {
+                for claim in claims {
+                    self.send_claim(ControllerCommands::ResolveOnchainMint(claim))
+                        .await;
+                }
+            }
+            (_, Some(block_number), Some(block_hash)) => {
+                tracing::debug!("Claims pending confirmation {:?}", claims);
+                pending.add(PendingClaim {
+                    tx_hash,
+                    log_index,
+                    block_number,
+                    block_hash,
+                    claims,
+                });
+            }
+            _ => tracing::warn!("Log without a block can't be confirmed, ignoring"),
+        }
+    }
+
+    // resolves claims deep enough, as long as their transaction is still in the block it was seen in
+    async fn confirm_pending(
+        &self,
+        rpc: &Arc<ChainListProvider>,
+        head: u64,
+        pending: &mut PendingClaims,
+    ) {
+        for confirmed in pending.confirmed_at(head) {
+            match rpc.get_transaction_receipt(confirmed.tx_hash).await {
+                Ok(Some(receipt)) if receipt.block_hash == Some(confirmed.block_hash) => {
+                    for claim in confirmed.claims {
+                        self.send_claim(ControllerCommands::ResolveOnchainMint(claim))
+                            .await;
+                    }
+                }
+                // gone or moved to another block, if it was mined again the new log comes through the subscription
+                Ok(_) => {
+                    tracing::info!(
+                        counter.pending_claims_reorged = 1,
+                        "Dropped pending claim that was reorged out"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!("Error getting receipt for pending claim: {}", e);
+                    pending.add(confirmed);
                 }
             }
         }
     }
 
+    async fn send_claim(&self, command: ControllerCommands) {
+        if let Err(err) = self.controller.send_command(command).await {
+            tracing::error!("Error sending claim to controller: {}", err);
+        }
+    }
+
     async fn make_provider(&self) -> eyre::Result<Arc<ChainListProvider>> {
         CHAINS.get_rpc(self.chain_id).await
     }
@@ -210,7 +666,112 @@ pub async fn get_contract_boot_nodes() -> eyre::Result<Vec<String>> {
 
 #[cfg(test)]
 mod test {
-    use crate::chain::get_contract_boot_nodes;
+    use super::*;
+    use crate::premints::zora_premint::contract::PREMINT_FACTORY_ADDR;
+    use alloy::primitives::LogData;
+    use std::str::FromStr;
+
+    fn preminted_log(address: Address) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address,
+                data: LogData::new(vec![B256::from_str("0xd7f3736994092942aacd1d75026379ceeaf4e28b6183b15f2decc9237334429b").unwrap(),
+                                        B256::from_str("0x00000000000000000000000065aae9d752ecac4965015664d0a6d0951e28d757").unwrap(),
+                                        B256::from_str("0x0000000000000000000000000000000000000000000000000000000000000001").unwrap(),
+                                        B256::from_str("0x0000000000000000000000000000000000000000000000000000000000000001").unwrap(),
+                    ],
+                    Bytes::from_str("0x0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000edb81afaecc2379635b25a752b787f821a46644c0000000000000000000000000000000000000000000000000000000000000001").unwrap()
+                ).unwrap()
+            },
+            transaction_hash: Some(
+                B256::from_str(
+                    "0xb28c6c91fc5c79490c0bf2e8b26ec7ea5ca66065e14436bf5798a9feaad6e617",
+                )
+                .unwrap(),
+            ),
+            log_index: Some(28),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pending_claims() {
+        let pending_claim = |log_index: u64, block_number: u64| PendingClaim {
+            tx_hash: B256::default(),
+            log_index,
+            block_number,
+            block_hash: B256::default(),
+            claims: vec![],
+        };
+
+        let mut pending = PendingClaims::new(3);
+        pending.add(pending_claim(0, 10));
+        pending.add(pending_claim(1, 12));
+        pending.add(pending_claim(2, 12));
+
+        assert!(pending.confirmed_at(12).is_empty());
+        assert_eq!(pending.cursor_for(20), 9);
+        assert_eq!(pending.confirmed_at(13), vec![pending_claim(0, 10)]);
+        assert_eq!(pending.cursor_for(20), 11);
+        assert_eq!(pending.cursor_for(5), 5);
+
+        // reorged out before it was confirmed
+        assert!(pending.remove(B256::default(), 1));
+        assert!(!pending.remove(B256::default(), 1));
+
+        assert_eq!(pending.confirmed_at(100), vec![pending_claim(2, 12)]);
+        assert!(pending.is_empty());
+        assert_eq!(pending.cursor_for(20), 20);
+    }
+
+    #[test]
+    fn test_subscription_progress() {
+        let mut progress = SubscriptionProgress::new(100);
+
+        // a quiet chain, only heads come in
+        progress.head(101);
+        progress.head(102);
+        assert_eq!(progress.checkpoint(), 100);
+        assert_eq!(progress.checkpoint(), 102);
+
+        // a log's block may have more logs coming
+        progress.log(110);
+        assert_eq!(progress.checkpoint(), 109);
+
+        progress.head(112);
+        assert_eq!(progress.checkpoint(), 109);
+        assert_eq!(progress.checkpoint(), 112);
+    }
+
+    #[test]
+    fn test_claim_routes() {
+        let kinds = vec![
+            PremintName("simple".to_string()),
+            PremintName("zora_premint_v2".to_string()),
+            PremintName("zora_premint_erc20v1".to_string()),
+        ];
+
+        // no zora premints on unsupported chains
+        assert!(claim_routes(&kinds, 1).is_empty());
+
+        let routes = claim_routes(&kinds, 7777777);
+        assert_eq!(routes.len(), 2);
+
+        // both zora types are claimed through the same event, so a log goes to each of them
+        let log = preminted_log(PREMINT_FACTORY_ADDR);
+        let claims: Vec<InclusionClaim> = routes
+            .iter()
+            .filter(|r| r.matches(&log))
+            .map(|r| r.map_claim(7777777, log.clone()).unwrap())
+            .collect();
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[0].kind, "zora_premint_v2");
+        assert_eq!(claims[1].kind, "zora_premint_erc20v1");
+        assert_eq!(claims[0].premint_id, claims[1].premint_id);
+
+        let other = preminted_log(Address::default());
+        assert!(routes.iter().all(|r| !r.matches(&other)));
+    }
 
     #[tokio::test]
     async fn test_get_bootnodes_from_chain() {
@@ -1,19 +1,428 @@
-use alloy::providers::{ProviderBuilder, RootProvider};
+use crate::multicall::MulticallBatcher;
+use alloy::primitives::{Address, Bytes, B256};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider};
 use alloy::pubsub::PubSubFrontend;
-use alloy::rpc::client::WsConnect;
-use eyre::ContextCompat;
+use alloy::rpc::client::{RpcClient, WsConnect};
+use alloy::rpc::types::eth::{BlockId, Filter, Log, TransactionReceipt, TransactionRequest};
+use alloy::transports::http::Http;
+use alloy::transports::{RpcError, TransportError};
+use futures::future::join_all;
 use mini_moka::sync::Cache;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
 
 const CHAINS_JSON: &str = include_str!("../data/chains.json");
 
-pub type ChainListProvider = RootProvider<PubSubFrontend>;
+/// Connection to a chain's RPC. Websocket when the chain has one since only that can subscribe
+/// to logs, otherwise http where logs have to be polled for.
+pub enum RpcProvider {
+    Ws(RootProvider<PubSubFrontend>),
+    Http(RootProvider<Http<reqwest::Client>>),
+}
+
+macro_rules! on_provider {
+    ($self:ident, $provider:ident => $body:expr) => {
+        match $self {
+            RpcProvider::Ws($provider) => $body,
+            RpcProvider::Http($provider) => $body,
+        }
+    };
+}
+
+impl RpcProvider {
+    pub async fn call(&self, tx: &TransactionRequest) -> eyre::Result<Bytes> {
+        Ok(on_provider!(self, p => p.call(tx, BlockId::latest()).await)?)
+    }
+
+    pub async fn get_block_number(&self) -> eyre::Result<u64> {
+        Ok(on_provider!(self, p => p.get_block_number().await)?)
+    }
+
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> eyre::Result<Option<TransactionReceipt>> {
+        Ok(on_provider!(self, p => p.get_transaction_receipt(hash).await)?)
+    }
+
+    pub async fn get_logs(&self, filter: &Filter) -> eyre::Result<Vec<Log>> {
+        Ok(on_provider!(self, p => p.get_logs(filter).await)?)
+    }
+
+    /// Websocket connection for subscriptions, None for http
+    pub fn pubsub(&self) -> Option<&RootProvider<PubSubFrontend>> {
+        match self {
+            RpcProvider::Ws(provider) => Some(provider),
+            RpcProvider::Http(_) => None,
+        }
+    }
+}
+
+/// How often the endpoints of a chain's pool are probed
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Probes taking longer than this count as failed
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Endpoints further behind the highest block seen in the pool are considered stale
+const MAX_BLOCK_LAG: u64 = 10;
+
+/// Health of a pool endpoint as of its last probe or request, shown in /summary
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EndpointStatus {
+    // without path or query, those often hold api keys
+    pub url: String,
+    pub healthy: bool,
+    pub block_number: Option<u64>,
+    pub latency_ms: Option<u64>,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    pub last_checked: Option<String>,
+}
+
+struct Endpoint {
+    url: String,
+    // from chains.json while CHAIN_<id>_RPC_WSS is set, only used when the overrides aren't healthy
+    fallback: bool,
+    // connected on first use, and dropped when the endpoint fails so it reconnects
+    provider: Mutex<Option<Arc<RpcProvider>>>,
+    status: Mutex<EndpointStatus>,
+}
+
+impl Endpoint {
+    fn new(url: String, fallback: bool) -> Self {
+        Self {
+            status: Mutex::new(EndpointStatus {
+                url: redact_url(&url),
+                // untested endpoints are tried like healthy ones
+                healthy: true,
+                block_number: None,
+                latency_ms: None,
+                failures: 0,
+                last_error: None,
+                last_checked: None,
+            }),
+            url,
+            fallback,
+            provider: Mutex::new(None),
+        }
+    }
+
+    fn is_ws(&self) -> bool {
+        self.url.starts_with("ws")
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.status.lock().unwrap().healthy
+    }
+
+    async fn provider(&self) -> eyre::Result<Arc<RpcProvider>> {
+        if let Some(provider) = self.provider.lock().unwrap().clone() {
+            return Ok(provider);
+        }
+
+        let provider = Arc::new(if self.is_ws() {
+            let conn = WsConnect::new(&self.url);
+            RpcProvider::Ws(ProviderBuilder::new().on_ws(conn).await?)
+        } else {
+            let client = RpcClient::new_http(self.url.parse()?);
+            RpcProvider::Http(ProviderBuilder::new().on_client(client))
+        });
+
+        *self.provider.lock().unwrap() = Some(provider.clone());
+        Ok(provider)
+    }
+
+    fn mark_failed(&self, err: &eyre::Report) {
+        *self.provider.lock().unwrap() = None;
+
+        let mut status = self.status.lock().unwrap();
+        status.healthy = false;
+        status.failures += 1;
+        status.last_error = Some(err.to_string());
+    }
+
+    // probes the endpoint, returning its block number and how long that took
+    async fn probe(&self) -> eyre::Result<(u64, Duration)> {
+        let start = Instant::now();
+        let block_number = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, async {
+            self.provider().await?.get_block_number().await
+        })
+        .await
+        .map_err(|_| eyre::eyre!("Health check timed out"))??;
+
+        Ok((block_number, start.elapsed()))
+    }
+}
+
+/// All RPC endpoints of a chain. Requests are spread round robin over the healthy endpoints
+/// and fail over to the next one when an endpoint can't be reached.
+pub struct ProviderPool {
+    chain_id: u64,
+    endpoints: Vec<Endpoint>,
+    next: AtomicUsize,
+}
+
+impl ProviderPool {
+    pub fn new(chain_id: u64, urls: &[String]) -> eyre::Result<Self> {
+        Self::with_fallbacks(chain_id, urls, &[])
+    }
+
+    /// Pool that prefers `urls` and only sends requests to `fallbacks` when none of `urls` is healthy
+    pub fn with_fallbacks(
+        chain_id: u64,
+        urls: &[String],
+        fallbacks: &[String],
+    ) -> eyre::Result<Self> {
+        if urls.is_empty() && fallbacks.is_empty() {
+            return Err(eyre::eyre!("No RPC URLs for chain {}", chain_id));
+        }
+        if urls
+            .iter()
+            .chain(fallbacks)
+            .any(|url| VARIABLE_REGEX.is_match(url))
+        {
+            return Err(eyre::eyre!("URL contains variables"));
+        }
+
+        let fallbacks = fallbacks.iter().filter(|url| !urls.contains(url));
+        Ok(Self {
+            chain_id,
+            endpoints: urls
+                .iter()
+                .map(|url| Endpoint::new(url.clone(), false))
+                .chain(fallbacks.map(|url| Endpoint::new(url.clone(), true)))
+                .collect(),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| endpoint.status.lock().unwrap().clone())
+            .collect()
+    }
+
+    // healthy endpoints starting from the next in turn with the fallbacks after the preferred
+    // ones, the unhealthy ones as a last resort
+    fn candidates(&self) -> Vec<&Endpoint> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.endpoints.len();
+        let mut rotated = self.endpoints[start..]
+            .iter()
+            .chain(&self.endpoints[..start])
+            .map(|endpoint| {
+                let rank = match (endpoint.is_healthy(), endpoint.fallback) {
+                    (true, false) => 0,
+                    (true, true) => 1,
+                    (false, _) => 2,
+                };
+                (rank, endpoint)
+            })
+            .collect::<Vec<_>>();
+
+        // stable, so each rank keeps the round robin order
+        rotated.sort_by_key(|(rank, _)| *rank);
+        rotated.into_iter().map(|(_, endpoint)| endpoint).collect()
+    }
+
+    async fn with_failover<T, F, Fut>(&self, request: F) -> eyre::Result<T>
+    where
+        F: Fn(Arc<RpcProvider>) -> Fut,
+        Fut: Future<Output = eyre::Result<T>>,
+    {
+        let mut last_error = None;
+        for (attempt, endpoint) in self.candidates().into_iter().enumerate() {
+            if attempt > 0 {
+                tracing::info!(counter.rpc_failovers = 1, chain_id = self.chain_id);
+            }
+
+            let result = match endpoint.provider().await {
+                Ok(provider) => request(provider).await,
+                Err(err) => Err(err),
+            };
+            match result {
+                Err(err) if is_endpoint_failure(&err) => {
+                    tracing::warn!(
+                        chain_id = self.chain_id,
+                        url = endpoint.status.lock().unwrap().url.as_str(),
+                        "RPC request failed, trying next endpoint: {:?}",
+                        err
+                    );
+                    endpoint.mark_failed(&err);
+                    last_error = Some(err);
+                }
+                result => return result,
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| eyre::eyre!("No RPC endpoints for chain")))
+    }
+
+    pub async fn call(&self, tx: &TransactionRequest) -> eyre::Result<Bytes> {
+        self.with_failover(|rpc| async move { rpc.call(tx).await })
+            .await
+    }
+
+    pub async fn get_block_number(&self) -> eyre::Result<u64> {
+        self.with_failover(|rpc| async move { rpc.get_block_number().await })
+            .await
+    }
+
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> eyre::Result<Option<TransactionReceipt>> {
+        self.with_failover(|rpc| async move { rpc.get_transaction_receipt(hash).await })
+            .await
+    }
+
+    pub async fn get_logs(&self, filter: &Filter) -> eyre::Result<Vec<Log>> {
+        self.with_failover(|rpc| async move { rpc.get_logs(filter).await })
+            .await
+    }
+
+    /// Websocket connection for subscriptions, None if no websocket endpoint can be connected to.
+    /// Sticks to the first healthy one so subscriptions don't move around needlessly.
+    pub async fn pubsub(&self) -> Option<Arc<RpcProvider>> {
+        let (healthy, unhealthy): (Vec<&Endpoint>, Vec<&Endpoint>) = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_ws())
+            .partition(|endpoint| endpoint.is_healthy());
+
+        for endpoint in healthy.into_iter().chain(unhealthy) {
+            match endpoint.provider().await {
+                Ok(provider) => return Some(provider),
+                Err(err) => endpoint.mark_failed(&err),
+            }
+        }
+        None
+    }
 
-pub struct Chains(Vec<Chain>, Cache<String, Arc<ChainListProvider>>);
+    /// Probes every endpoint, marking those that fail or lag behind the others as unhealthy
+    pub async fn check_health(&self) {
+        let probes = join_all(self.endpoints.iter().map(|endpoint| endpoint.probe())).await;
+        // fallbacks are only compared with each other, a local fork the overrides point at is
+        // behind the public RPCs but is still the one to use
+        let highest = |fallback: bool| {
+            self.endpoints
+                .iter()
+                .zip(&probes)
+                .filter(|(endpoint, _)| endpoint.fallback == fallback)
+                .filter_map(|(_, probe)| probe.as_ref().ok())
+                .map(|(block_number, _)| *block_number)
+                .max()
+                .unwrap_or_default()
+        };
+        let highest = [highest(false), highest(true)];
+        let now = chrono::Utc::now().to_rfc3339();
+
+        for (endpoint, probe) in self.endpoints.iter().zip(probes) {
+            match probe {
+                Ok((block_number, latency)) => {
+                    let highest = highest[endpoint.fallback as usize];
+                    let mut status = endpoint.status.lock().unwrap();
+                    tracing::info!(
+                        histogram.rpc_probe_latency = latency.as_millis() as u64,
+                        chain_id = self.chain_id,
+                        url = status.url.as_str()
+                    );
+
+                    status.healthy = block_number + MAX_BLOCK_LAG >= highest;
+                    status.block_number = Some(block_number);
+                    status.latency_ms = Some(latency.as_millis() as u64);
+                    status.last_error = match status.healthy {
+                        true => None,
+                        false => Some(format!("{} blocks behind", highest - block_number)),
+                    };
+                    status.last_checked = Some(now.clone());
+                }
+                Err(err) => {
+                    endpoint.mark_failed(&err);
+                    endpoint.status.lock().unwrap().last_checked = Some(now.clone());
+                }
+            }
+        }
+
+        let healthy = self.endpoints.iter().filter(|e| e.is_healthy()).count();
+        tracing::info!(
+            histogram.rpc_healthy_endpoints = healthy as u64,
+            chain_id = self.chain_id
+        );
+    }
+}
+
+// errors returned by the node itself (e.g. execution reverted) aren't the endpoint's fault
+fn is_endpoint_failure(err: &eyre::Report) -> bool {
+    !matches!(
+        err.downcast_ref::<TransportError>(),
+        Some(RpcError::ErrorResp(_))
+    )
+}
+
+fn redact_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => format!(
+            "{}://{}",
+            parsed.scheme(),
+            parsed.host_str().unwrap_or_default()
+        ),
+        Err(_) => "<invalid url>".to_string(),
+    }
+}
+
+// probes the pool until it's dropped from the chains cache
+async fn watch_health(pool: Weak<ProviderPool>) {
+    let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        match pool.upgrade() {
+            Some(pool) => pool.check_health().await,
+            None => return,
+        }
+    }
+}
+
+/// RPC endpoints of a chain, with view calls batched into multicalls
+pub struct ChainListProvider {
+    pool: Arc<ProviderPool>,
+    multicall: MulticallBatcher,
+}
+
+impl ChainListProvider {
+    pub fn new(pool: ProviderPool) -> Self {
+        let pool = Arc::new(pool);
+        Self {
+            multicall: MulticallBatcher::new(pool.clone()),
+            pool,
+        }
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.pool.chain_id
+    }
+
+    /// Calls a view function, batched with other calls made on the chain at the same time
+    pub async fn view_call(&self, to: Address, input: Bytes) -> eyre::Result<Bytes> {
+        self.multicall.call(to, input).await
+    }
+}
+
+impl Deref for ChainListProvider {
+    type Target = ProviderPool;
+
+    fn deref(&self) -> &ProviderPool {
+        &self.pool
+    }
+}
+
+pub struct Chains(Vec<Chain>, Cache<u64, Arc<ChainListProvider>>);
 
 pub static CHAINS: Lazy<Chains> = Lazy::new(Chains::new);
 static VARIABLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\{(.+?)}").unwrap());
@@ -35,58 +444,82 @@ impl Chains {
             .cloned()
     }
 
-    pub fn get_rpc_url(&self, chain_id: u64) -> eyre::Result<String> {
-        // allow tests to override the chains.json to they can dynamically set the RPC when
-        // integration testing against anvil
-        if let Ok(url) = std::env::var(format!("CHAIN_{}_RPC_WSS", chain_id)) {
-            return Ok(url);
-        }
-
-        let chain = self
-            .get_chain_by_id(chain_id)
-            .wrap_err(format!("Chain id {} not found", chain_id))?;
+    /// Every usable RPC URL of a chain: the CHAIN_<id>_RPC_WSS overrides, then those in
+    /// chains.json with websockets first
+    pub fn get_rpc_urls(&self, chain_id: u64) -> eyre::Result<Vec<String>> {
+        let (overrides, fallbacks) = self.rpc_urls(chain_id)?;
+        let mut urls = overrides;
+        urls.extend(fallbacks.into_iter().filter(|url| !urls.contains(url)));
+        Ok(urls)
+    }
 
-        for rpc in chain.rpc.iter() {
-            if !rpc.starts_with("ws") {
-                continue;
-            }
+    // the overrides and the chains.json urls, at least one of them non empty
+    fn rpc_urls(&self, chain_id: u64) -> eyre::Result<(Vec<String>, Vec<String>)> {
+        // allow tests to set the RPC dynamically when integration testing against anvil, or
+        // operators to add their own. Takes a comma separated list.
+        let overrides = std::env::var(format!("CHAIN_{}_RPC_WSS", chain_id))
+            .map(|urls| urls.split(',').map(|url| url.trim().to_string()).collect())
+            .unwrap_or_else(|_| Vec::new());
 
-            return Ok(rpc.clone());
+        let chain = self.get_chain_by_id(chain_id);
+        if chain.is_none() && overrides.is_empty() {
+            return Err(eyre::eyre!("Chain id {} not found", chain_id));
         }
 
-        Err(eyre::eyre!("No suitable RPC URL found for chain"))
+        let urls = chain
+            .map(|chain| usable_rpc_urls(&chain.rpc))
+            .unwrap_or_default();
+        match urls.is_empty() && overrides.is_empty() {
+            true => Err(eyre::eyre!("No suitable RPC URL found for chain")),
+            false => Ok((overrides, urls)),
+        }
     }
 
     pub async fn get_rpc(&self, chain_id: u64) -> eyre::Result<Arc<ChainListProvider>> {
-        match self.get_rpc_url(chain_id) {
-            Ok(url) => self.connect(&url).await,
-            Err(e) => Err(e),
+        if let Some(provider) = self.1.get(&chain_id) {
+            return Ok(provider);
         }
-    }
 
-    async fn connect(&self, url: &String) -> eyre::Result<Arc<ChainListProvider>> {
-        if VARIABLE_REGEX.is_match(url) {
-            return Err(eyre::eyre!("URL contains variables"));
-        }
+        let (overrides, fallbacks) = self.rpc_urls(chain_id)?;
+        let provider = self.connect_pool(ProviderPool::with_fallbacks(
+            chain_id, &overrides, &fallbacks,
+        )?);
+        // keep a copy in the cache
+        self.1.insert(chain_id, provider.clone());
+        Ok(provider)
+    }
 
-        let cached = self.1.get(url);
-        match cached {
-            Some(provider) => Ok(provider),
-            None => {
-                let conn = WsConnect::new(url);
-                let provider = ProviderBuilder::new().on_ws(conn).await?;
+    /// Status of the endpoints of every chain that is in use
+    pub fn endpoint_status(&self) -> HashMap<u64, Vec<EndpointStatus>> {
+        self.1
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().status()))
+            .collect()
+    }
 
-                let arc = Arc::new(provider);
+    fn connect(&self, chain_id: u64, urls: &[String]) -> eyre::Result<Arc<ChainListProvider>> {
+        Ok(self.connect_pool(ProviderPool::new(chain_id, urls)?))
+    }
 
-                // keep a copy in the cache
-                self.1.insert(url.clone(), arc.clone());
+    fn connect_pool(&self, pool: ProviderPool) -> Arc<ChainListProvider> {
+        let provider = Arc::new(ChainListProvider::new(pool));
+        tokio::spawn(watch_health(Arc::downgrade(&provider.pool)));
 
-                Ok(arc)
-            }
-        }
+        provider
     }
 }
 
+// websockets first, skipping urls that need api keys filled in
+fn usable_rpc_urls(rpcs: &[String]) -> Vec<String> {
+    let usable = || rpcs.iter().filter(|rpc| !VARIABLE_REGEX.is_match(rpc));
+
+    usable()
+        .filter(|rpc| rpc.starts_with("ws"))
+        .chain(usable().filter(|rpc| rpc.starts_with("http")))
+        .cloned()
+        .collect()
+}
+
 // types created by https://transform.tools/json-to-rust-serde
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -101,7 +534,6 @@ pub struct Chain {
 #[cfg(test)]
 mod test {
     use super::*;
-    use alloy::providers::Provider;
 
     #[test]
     fn test_chains_new() {
@@ -127,10 +559,74 @@ mod test {
         assert!(number > 0);
     }
 
+    #[test]
+    fn test_usable_rpc_urls() {
+        let rpcs = |urls: &[&str]| urls.iter().map(|u| u.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            usable_rpc_urls(&rpcs(&[
+                "https://rpc.zora.energy/",
+                "wss://rpc.zora.energy"
+            ])),
+            rpcs(&["wss://rpc.zora.energy", "https://rpc.zora.energy/"])
+        );
+        assert_eq!(
+            usable_rpc_urls(&rpcs(&[
+                "https://arbitrum-mainnet.infura.io/v3/${INFURA_API_KEY}",
+                "https://arb1.arbitrum.io/rpc",
+            ])),
+            rpcs(&["https://arb1.arbitrum.io/rpc"])
+        );
+        assert!(
+            usable_rpc_urls(&rpcs(&["wss://mainnet.infura.io/ws/v3/${INFURA_API_KEY}"])).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_get_rpc_urls_overrides() {
+        let chain = CHAINS.get_chain_by_id(8453).unwrap();
+        std::env::set_var(
+            "CHAIN_8453_RPC_WSS",
+            "ws://localhost:8545, http://localhost:8545",
+        );
+
+        let urls = CHAINS.get_rpc_urls(8453).unwrap();
+        assert_eq!(urls[..2], ["ws://localhost:8545", "http://localhost:8545"]);
+        assert_eq!(urls[2..], usable_rpc_urls(&chain.rpc));
+
+        // chains missing from chains.json only need the overrides
+        assert!(CHAINS.get_rpc_urls(999999999).is_err());
+        std::env::set_var("CHAIN_999999999_RPC_WSS", "ws://localhost:8545");
+        assert_eq!(
+            CHAINS.get_rpc_urls(999999999).unwrap(),
+            vec!["ws://localhost:8545".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("https://base-mainnet.g.alchemy.com/v2/secret"),
+            "https://base-mainnet.g.alchemy.com"
+        );
+        assert_eq!(redact_url("wss://rpc.zora.energy"), "wss://rpc.zora.energy");
+    }
+
+    #[tokio::test]
+    async fn test_chain_connect_http() {
+        let provider = CHAINS
+            .connect(7777777, &["https://rpc.zora.energy/".to_string()])
+            .unwrap();
+        assert!(provider.pubsub().await.is_none());
+
+        let number = provider.get_block_number().await.unwrap();
+        assert!(number > 0);
+    }
+
     #[tokio::test]
     async fn test_chain_connect_variable() {
         let url = "https://mainnet.infura.io/v3/${INFURA_API_KEY}".to_string();
-        let provider = CHAINS.connect(&url).await;
+        let provider = CHAINS.connect(1, &[url]);
 
         assert!(provider.is_err());
         match provider {
@@ -138,4 +634,58 @@ mod test {
             Err(e) => assert!(e.to_string().contains("URL contains variables")),
         }
     }
+
+    #[tokio::test]
+    async fn test_pool_failover() {
+        // nothing listens on port 1
+        let pool = ProviderPool::new(
+            7777777,
+            &[
+                "http://127.0.0.1:1".to_string(),
+                "https://rpc.zora.energy/".to_string(),
+            ],
+        )
+        .unwrap();
+
+        let number = pool.get_block_number().await.unwrap();
+        assert!(number > 0);
+
+        let status = pool.status();
+        assert!(!status[0].healthy);
+        assert_eq!(status[0].failures, 1);
+        assert!(status[1].healthy);
+
+        // the dead endpoint is only tried after the healthy one from now on
+        assert_eq!(pool.candidates()[0].url, "https://rpc.zora.energy/");
+
+        pool.check_health().await;
+        let status = pool.status();
+        assert!(!status[0].healthy);
+        assert!(status[1].healthy);
+        assert!(status[1].block_number.unwrap() >= number);
+        assert!(status[1].last_checked.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_pool_fallbacks() {
+        let pool = ProviderPool::with_fallbacks(
+            7777777,
+            &["http://127.0.0.1:1".to_string()],
+            &[
+                "http://127.0.0.1:1".to_string(),
+                "https://rpc.zora.energy/".to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(pool.endpoints.len(), 2);
+
+        // the override is tried first however the round robin turns
+        assert_eq!(pool.candidates()[0].url, "http://127.0.0.1:1");
+        assert_eq!(pool.candidates()[0].url, "http://127.0.0.1:1");
+
+        // and the fallback takes over once it fails
+        let number = pool.get_block_number().await.unwrap();
+        assert!(number > 0);
+        assert_eq!(pool.candidates()[0].url, "https://rpc.zora.energy/");
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use envconfig::Envconfig;
@@ -37,7 +38,10 @@ pub struct Config {
     pub peer_limit: u64,
 
     // Comma separated list of default premint types to process
-    #[envconfig(from = "PREMINT_TYPES", default = "zora_premint_v2")]
+    #[envconfig(
+        from = "PREMINT_TYPES",
+        default = "zora_premint_v2,zora_premint_erc20v1"
+    )]
     pub supported_premint_types: String,
 
     #[envconfig(from = "CHAIN_INCLUSION_MODE", default = "verify")]
@@ -79,8 +83,63 @@ pub struct Config {
     #[envconfig(from = "SYNC_LOOKBACK_HOURS", default = "6")]
     pub sync_lookback_hours: u64,
 
+    // number of peers to sync with in parallel
+    #[envconfig(from = "SYNC_PEERS", default = "3")]
+    pub sync_peers: usize,
+
+    #[envconfig(from = "SYNC_TIMEOUT_SECS", default = "30")]
+    pub sync_timeout_secs: u64,
+
+    // peers with an application score at or below this get disconnected and banned
+    #[envconfig(from = "PEER_BAN_THRESHOLD", default = "-100")]
+    pub peer_ban_threshold: f64,
+
+    #[envconfig(from = "PEER_BAN_MINUTES", default = "60")]
+    pub peer_ban_minutes: u64,
+
     #[envconfig(from = "ENABLE_RELAY_SERVER", default = "false")]
     pub enable_relay_server: bool,
+
+    // deliveries that still fail after this many attempts end up as dead letters
+    #[envconfig(from = "WEBHOOK_MAX_ATTEMPTS", default = "5")]
+    pub webhook_max_attempts: u32,
+
+    #[envconfig(from = "WEBHOOK_TIMEOUT_SECS", default = "10")]
+    pub webhook_timeout_secs: u64,
+
+    // blocks a mint needs on top of it before its premint is marked seen on chain, 0 marks right away
+    #[envconfig(from = "CONFIRMATION_DEPTH", default = "0")]
+    pub confirmation_depth: u64,
+
+    // per chain overrides of the confirmation depth. Ex: 7777777:5,8453:10
+    #[envconfig(from = "CHAIN_CONFIRMATION_DEPTHS")]
+    pub chain_confirmation_depths: Option<String>,
+
+    // seconds between batches of active premints re-checked against chain state, 0 disables it
+    #[envconfig(from = "RECONCILE_INTERVAL_SECS", default = "60")]
+    pub reconcile_interval_secs: u64,
+
+    #[envconfig(from = "RECONCILE_BATCH_SIZE", default = "50")]
+    pub reconcile_batch_size: u64,
+
+    // rules still running after this long count as errors instead of holding up the controller
+    #[envconfig(from = "RULE_TIMEOUT_MS", default = "5000")]
+    pub rule_timeout_ms: u64,
+
+    // sliding window of the per signer and per collection premint quotas
+    #[envconfig(from = "RATE_LIMIT_WINDOW_SECS", default = "3600")]
+    pub rate_limit_window_secs: u64,
+
+    // new premints a signer or a collection may add to the pool per window, 0 means no limit
+    #[envconfig(from = "MAX_PREMINTS_PER_SIGNER", default = "0")]
+    pub max_premints_per_signer: u64,
+
+    #[envconfig(from = "MAX_PREMINTS_PER_COLLECTION", default = "0")]
+    pub max_premints_per_collection: u64,
+
+    // TOML or JSON file switching built-in rules on or off and setting up declarative rules
+    #[envconfig(from = "RULES_CONFIG_PATH")]
+    pub rules_config_path: Option<String>,
 }
 
 impl Config {
@@ -107,7 +166,22 @@ impl Config {
             rate_limit_rps: 1,
             boot_nodes: BootNodes::None,
             sync_lookback_hours: 6,
+            sync_peers: 3,
+            sync_timeout_secs: 30,
+            peer_ban_threshold: -100.0,
+            peer_ban_minutes: 60,
             enable_relay_server: false,
+            webhook_max_attempts: 5,
+            webhook_timeout_secs: 10,
+            confirmation_depth: 0,
+            chain_confirmation_depths: None,
+            reconcile_interval_secs: 0,
+            reconcile_batch_size: 50,
+            rule_timeout_ms: 5000,
+            rate_limit_window_secs: 3600,
+            max_premints_per_signer: 0,
+            max_premints_per_collection: 0,
+            rules_config_path: None,
         }
     }
 }
@@ -174,6 +248,34 @@ impl Config {
             .collect()
     }
 
+    /// Per chain overrides of the confirmation depth, fails on the first pair that isn't chain_id:depth
+    pub fn chain_confirmation_depths(&self) -> eyre::Result<HashMap<u64, u64>> {
+        self.chain_confirmation_depths
+            .iter()
+            .flat_map(|depths| depths.split(','))
+            .map(|pair| {
+                pair.split_once(':')
+                    .and_then(|(id, depth)| {
+                        Some((id.trim().parse().ok()?, depth.trim().parse().ok()?))
+                    })
+                    .ok_or_else(|| {
+                        eyre::eyre!(
+                            "Invalid CHAIN_CONFIRMATION_DEPTHS entry {:?}, expected chain_id:depth",
+                            pair.trim()
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    pub fn confirmations_for(&self, chain_id: u64) -> eyre::Result<u64> {
+        Ok(self
+            .chain_confirmation_depths()?
+            .get(&chain_id)
+            .copied()
+            .unwrap_or(self.confirmation_depth))
+    }
+
     pub fn trusted_peers(&self) -> Vec<PeerId> {
         match &self.trusted_peers {
             None => vec![],
@@ -234,6 +336,30 @@ mod test {
         assert_eq!(names[0].0, "zora_premint_v2");
     }
 
+    #[test]
+    fn test_confirmations_for() {
+        let config = Config {
+            confirmation_depth: 2,
+            chain_confirmation_depths: Some("7777777:5, 8453:10".to_string()),
+            ..Config::test_default()
+        };
+
+        assert_eq!(config.confirmations_for(7777777).unwrap(), 5);
+        assert_eq!(config.confirmations_for(8453).unwrap(), 10);
+        assert_eq!(config.confirmations_for(1).unwrap(), 2);
+        assert_eq!(
+            Config::test_default().confirmations_for(7777777).unwrap(),
+            0
+        );
+
+        let malformed = Config {
+            chain_confirmation_depths: Some("7777777:5,8453:abc".to_string()),
+            ..Config::test_default()
+        };
+        let err = malformed.confirmations_for(7777777).unwrap_err();
+        assert!(err.to_string().contains("8453:abc"));
+    }
+
     #[test]
     fn test_parse_bootnodes() {
         assert_eq!(BootNodes::from_str("chain").unwrap(), BootNodes::Chain);
@@ -1,17 +1,34 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use eyre::WrapErr;
 use futures_ticker::Ticker;
+use futures_util::future::join_all;
 use futures_util::StreamExt;
-use sqlx::SqlitePool;
+use libp2p::gossipsub::MessageId;
+use libp2p::PeerId;
+use sqlx::AnyPool;
 use tokio::select;
-use tokio::sync::{mpsc, oneshot, Semaphore};
+use tokio::sync::{broadcast, mpsc, oneshot, Semaphore};
 
 use crate::chain::inclusion_claim_correct;
 use crate::config::{ChainInclusionMode, Config};
+use crate::events;
+use crate::events::PremintEvent;
+use crate::multi_ticker::MultiTicker;
 use crate::p2p::NetworkState;
-use crate::rules::{Results, RulesEngine};
-use crate::storage::{list_all_with_options, PremintStorage, QueryOptions, Reader, Writer};
+use crate::reputation::PeerOffense;
+use crate::rules::{Evaluation, Results, RulesEngine, RulesSummary};
+use crate::rules_config::RulesConfig;
+use crate::storage::{
+    list_page_with_options, PremintPage, PremintSource, PremintStorage, QueryOptions, Reader,
+    Writer,
+};
+use crate::sync::{
+    follow_up_requests, respond_to_tree_sync, verify_tree_sync_response, PeerSyncStats, TreeRoot,
+    TreeSyncRequest, TreeSyncResponse,
+};
 use crate::types::{
     InclusionClaim, MintpoolNodeInfo, PeerInclusionClaim, PremintName, PremintTypes,
 };
@@ -37,20 +54,51 @@ pub enum SwarmCommand {
     Sync {
         query: QueryOptions,
     },
+    TreeSync {
+        peer_id: PeerId,
+        request: TreeSyncRequest,
+    },
+    ReportPeer {
+        peer_id: PeerId,
+        offense: PeerOffense,
+    },
+    ReportPremintValidation {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        evaluation: Evaluation,
+    },
 }
 
 /// Event types that may be received from the p2p swarm that need to be handled by the controller
 pub enum P2PEvent {
     NetworkState(NetworkState),
-    PremintReceived(PremintTypes),
+    PremintReceived {
+        premint: PremintTypes,
+        from_peer_id: PeerId,
+        message_id: MessageId,
+    },
     MintSeenOnchain(PeerInclusionClaim),
     SyncRequest {
         query: QueryOptions,
-        channel: oneshot::Sender<eyre::Result<Vec<PremintTypes>>>,
+        channel: oneshot::Sender<eyre::Result<PremintPage>>,
     },
     SyncResponse {
+        peer_id: PeerId,
+        query: QueryOptions,
         premints: Vec<PremintTypes>,
     },
+    TreeSyncRequest {
+        request: TreeSyncRequest,
+        channel: oneshot::Sender<eyre::Result<TreeSyncResponse>>,
+    },
+    TreeSyncResponse {
+        peer_id: PeerId,
+        request: TreeSyncRequest,
+        response: TreeSyncResponse,
+    },
+    SyncTimeout {
+        peer_id: PeerId,
+    },
 }
 
 pub enum ControllerCommands {
@@ -70,12 +118,44 @@ pub enum ControllerCommands {
     },
     Query(DBQuery),
     ResolveOnchainMint(InclusionClaim),
+    // claim sent with ResolveOnchainMint was reorged out
+    RevertOnchainMint(InclusionClaim),
     Sync,
+    ReturnSyncStats {
+        channel: oneshot::Sender<HashMap<PeerId, PeerSyncStats>>,
+    },
+    SubscribeEvents {
+        channel: oneshot::Sender<broadcast::Receiver<PremintEvent>>,
+    },
+    // evaluates the rules without storing or broadcasting the premint
+    ValidatePremint {
+        premint: PremintTypes,
+        channel: oneshot::Sender<eyre::Result<Results>>,
+    },
+    ReturnRules {
+        channel: oneshot::Sender<RulesSummary>,
+    },
+    UpdateRules {
+        config: RulesConfig,
+        channel: oneshot::Sender<eyre::Result<RulesSummary>>,
+    },
+    // rule results of a reconcile batch, evaluated off the controller loop
+    ReconcileResults {
+        results: Vec<(PremintTypes, eyre::Result<Results>)>,
+        started: Instant,
+    },
 }
 
 pub enum DBQuery {
     ListAll(oneshot::Sender<eyre::Result<Vec<PremintTypes>>>),
-    Direct(oneshot::Sender<eyre::Result<SqlitePool>>),
+    Direct(oneshot::Sender<eyre::Result<AnyPool>>),
+    TreeRoots(oneshot::Sender<eyre::Result<Vec<TreeRoot>>>),
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum ControllerTickers {
+    Sync,
+    Reconcile,
 }
 
 /// Central hub for processing incoming events and commands from peers and other inputs
@@ -83,9 +163,17 @@ pub struct Controller {
     swarm_command_sender: mpsc::Sender<SwarmCommand>,
     swarm_event_receiver: mpsc::Receiver<P2PEvent>,
     external_commands: mpsc::Receiver<ControllerCommands>,
+    // for work spawned off the loop to report back
+    command_sender: mpsc::Sender<ControllerCommands>,
     store: PremintStorage,
-    rules: RulesEngine<PremintStorage>,
-    sync_ticker: Ticker,
+    rules: Arc<RulesEngine<PremintStorage>>,
+    tickers: MultiTicker<ControllerTickers>,
+    // rules re-run on active premints, with where the walk over them is at
+    reconcile_rules: Arc<RulesEngine<PremintStorage>>,
+    reconcile_cursor: Option<String>,
+    reconciling: bool,
+    sync_stats: HashMap<PeerId, PeerSyncStats>,
+    events: broadcast::Sender<PremintEvent>,
 
     config: Config,
 }
@@ -96,24 +184,48 @@ impl Controller {
         swarm_command_sender: mpsc::Sender<SwarmCommand>,
         swarm_event_receiver: mpsc::Receiver<P2PEvent>,
         external_commands: mpsc::Receiver<ControllerCommands>,
+        command_sender: mpsc::Sender<ControllerCommands>,
         store: PremintStorage,
         rules: RulesEngine<PremintStorage>,
     ) -> Self {
-        // sync every 60 minutes, also sync 5 seconds after startup (gives some time to connect to peers)
-        let sync_ticker =
-            Ticker::new_with_next(Duration::from_secs(60 * 60), Duration::from_secs(5));
+        let mut tickers = vec![(
+            // sync every 60 minutes, also sync 5 seconds after startup (gives some time to connect to peers)
+            ControllerTickers::Sync,
+            Ticker::new_with_next(Duration::from_secs(60 * 60), Duration::from_secs(5)),
+        )];
+        if config.enable_rpc && config.reconcile_interval_secs > 0 {
+            tickers.push((
+                ControllerTickers::Reconcile,
+                Ticker::new(Duration::from_secs(config.reconcile_interval_secs)),
+            ));
+        }
+
+        // rules switched off in the rules config don't evict premints either
+        let reconcile_rules = RulesEngine::new_with_chain_state_rules(&config);
+        reconcile_rules.apply_toggles(&rules.config());
 
         Self {
             swarm_command_sender,
             swarm_event_receiver,
             external_commands,
+            command_sender,
             store,
-            rules,
-            sync_ticker,
+            rules: Arc::new(rules),
+            tickers: MultiTicker::new(tickers),
+            reconcile_rules: Arc::new(reconcile_rules),
+            reconcile_cursor: None,
+            reconciling: false,
+            sync_stats: HashMap::new(),
+            events: events::channel(),
             config,
         }
     }
 
+    /// Live premint events, see `PremintEvent`
+    pub fn subscribe_events(&self) -> broadcast::Receiver<PremintEvent> {
+        self.events.subscribe()
+    }
+
     pub async fn run_loop(&mut self) {
         loop {
             select! {
@@ -125,8 +237,11 @@ impl Controller {
                 Some(event) = self.swarm_event_receiver.recv() => {
                     self.handle_event(event).await;
                 }
-                _ = self.sync_ticker.next() => {
-                    self.request_sync().await;
+                Some((ticker, _)) = self.tickers.next() => {
+                    match ticker {
+                        ControllerTickers::Sync => self.request_sync().await,
+                        ControllerTickers::Reconcile => self.reconcile_batch().await,
+                    }
                 }
             }
         }
@@ -148,16 +263,46 @@ impl Controller {
             .expect("Error sending sync command to swarm");
     }
 
-    pub async fn handle_event(&self, event: P2PEvent) {
+    pub async fn handle_event(&mut self, event: P2PEvent) {
         match event {
             P2PEvent::NetworkState(network_state) => {
                 tracing::info!("Current network state: {:?}", network_state);
             }
-            P2PEvent::PremintReceived(premint) => {
+            P2PEvent::PremintReceived {
+                premint,
+                from_peer_id,
+                message_id,
+            } => {
                 tracing::debug!(premint = premint.to_json().ok(), "Received premint");
 
-                // TODO: handle error? respond with error summary?
-                let _ = self.validate_and_insert(premint).await;
+                let evaluation = match self
+                    .validate_and_insert(premint, PremintSource::Gossip(from_peer_id))
+                    .await
+                {
+                    Ok(_) => Evaluation::Accept,
+                    Err(err) => match err.downcast_ref::<Results>() {
+                        Some(results) if results.blames_sender() => {
+                            Evaluation::Reject(results.only_failures().to_string())
+                        }
+                        // over a quota, the premint is fine but we won't store it
+                        Some(results) => Evaluation::Ignore(results.only_failures().to_string()),
+                        // couldn't evaluate or store it, or we already have it, which doesn't
+                        // make the premint invalid
+                        None => Evaluation::Ignore(err.to_string()),
+                    },
+                };
+
+                if let Err(err) = self
+                    .swarm_command_sender
+                    .send(SwarmCommand::ReportPremintValidation {
+                        message_id,
+                        propagation_source: from_peer_id,
+                        evaluation,
+                    })
+                    .await
+                {
+                    tracing::error!("Error sending premint validation to swarm: {:?}", err);
+                }
                 tracing::info!(histogram.premint_received = 1);
             }
             P2PEvent::MintSeenOnchain(claim) => {
@@ -166,22 +311,175 @@ impl Controller {
                 }
             }
             P2PEvent::SyncRequest { query, channel } => {
-                let events = list_all_with_options(&self.store.db(), &query).await;
-                if let Err(Err(err)) = channel.send(events) {
+                let page = list_page_with_options(&self.store.db(), &query).await;
+                if let Err(Err(err)) = channel.send(page) {
                     tracing::error!("Error sending sync response: {:?}", err);
                 }
                 tracing::info!(histogram.sync_request_processed = 1);
             }
-            P2PEvent::SyncResponse { premints } => {
-                let sem = Semaphore::new(10);
-                futures_util::future::join_all(premints.into_iter().map(|p| async {
-                    let permit = sem.acquire().await.unwrap();
-                    let _ = self.validate_and_insert(p).await;
-                    drop(permit);
-                }))
+            P2PEvent::SyncResponse {
+                peer_id,
+                query,
+                premints,
+            } => {
+                // only keep what we asked for
+                let (premints, invalid): (Vec<_>, Vec<_>) = premints
+                    .into_iter()
+                    .partition(|p| query.matches(&p.metadata()));
+
+                self.record_sync_response(peer_id, invalid.len(), premints)
+                    .await;
+            }
+            P2PEvent::TreeSyncRequest { request, channel } => {
+                let response = respond_to_tree_sync(self.store.index(), request, &self.store).await;
+                if channel.send(response).is_err() {
+                    tracing::error!("Error sending tree sync response");
+                }
+                tracing::info!(histogram.tree_sync_request_processed = 1);
+            }
+            P2PEvent::TreeSyncResponse {
+                peer_id,
+                request,
+                response,
+            } => {
+                if let Err(err) = self
+                    .handle_tree_sync_response(peer_id, request, response)
+                    .await
+                {
+                    tracing::error!("Error handling tree sync response: {:?}", err);
+                }
+            }
+            P2PEvent::SyncTimeout { peer_id } => {
+                self.sync_stats.entry(peer_id).or_default().timeouts += 1;
+                tracing::info!(histogram.sync_timeouts = 1);
+            }
+        }
+    }
+
+    /// Validates and stores premints received from a peer during sync,
+    /// returns how many were accepted and how many were rejected by the rules
+    async fn insert_synced_premints(
+        &self,
+        peer_id: PeerId,
+        premints: Vec<PremintTypes>,
+    ) -> (u64, u64) {
+        let sem = Semaphore::new(10);
+        let results = futures_util::future::join_all(premints.into_iter().map(|p| async {
+            let permit = sem.acquire().await.unwrap();
+            let result = self
+                .validate_and_insert(p, PremintSource::Sync(peer_id))
+                .await;
+            drop(permit);
+            result
+        }))
+        .await;
+
+        results
+            .iter()
+            .fold((0, 0), |(accepted, rejected), result| match result {
+                Ok(_) => (accepted + 1, rejected),
+                // premints we already have or that are over a quota aren't the peer's fault,
+                // see Results::blames_sender
+                Err(err)
+                    if err
+                        .downcast_ref::<Results>()
+                        .is_some_and(Results::blames_sender) =>
+                {
+                    (accepted, rejected + 1)
+                }
+                Err(_) => (accepted, rejected),
+            })
+    }
+
+    async fn record_sync_response(
+        &mut self,
+        peer_id: PeerId,
+        invalid: usize,
+        premints: Vec<PremintTypes>,
+    ) {
+        let (accepted, rejected) = self.insert_synced_premints(peer_id, premints).await;
+
+        let stats = self.sync_stats.entry(peer_id).or_default();
+        stats.responses += 1;
+        stats.accepted += accepted;
+        stats.rejected += rejected;
+        stats.invalid += invalid as u64;
+
+        if invalid > 0 {
+            tracing::warn!(
+                peer_id = peer_id.to_string(),
+                invalid = invalid,
+                "peer sent premints that were not requested"
+            );
+            self.report_peer(peer_id, PeerOffense::InvalidSyncResponse)
                 .await;
+        }
+        // one report per response, rules may just differ between us and the peer
+        if rejected > 0 {
+            self.report_peer(peer_id, PeerOffense::RuleRejection).await;
+        }
+    }
+
+    async fn report_peer(&self, peer_id: PeerId, offense: PeerOffense) {
+        if let Err(err) = self
+            .swarm_command_sender
+            .send(SwarmCommand::ReportPeer { peer_id, offense })
+            .await
+        {
+            tracing::error!("Error reporting peer to swarm: {:?}", err);
+        }
+    }
+
+    async fn handle_tree_sync_response(
+        &mut self,
+        peer_id: PeerId,
+        request: TreeSyncRequest,
+        response: TreeSyncResponse,
+    ) -> eyre::Result<()> {
+        let (response, invalid) = match verify_tree_sync_response(&request, response) {
+            Ok(verified) => verified,
+            Err(err) => {
+                self.sync_stats.entry(peer_id).or_default().invalid += 1;
+                self.report_peer(peer_id, PeerOffense::InvalidSyncResponse)
+                    .await;
+                return Err(err);
+            }
+        };
+
+        match response {
+            TreeSyncResponse::Summary {
+                prefix,
+                depth,
+                tree,
+                versions,
+            } => {
+                let index = self.store.index();
+                let requests = index.read(|local| {
+                    follow_up_requests(local, &prefix, depth, tree, &versions, |path| {
+                        index.version(path)
+                    })
+                });
+
+                for request in requests {
+                    self.swarm_command_sender
+                        .send(SwarmCommand::TreeSync { peer_id, request })
+                        .await?;
+                }
+            }
+            TreeSyncResponse::Premints(premints) => {
+                tracing::info!(histogram.tree_sync_premints_received = premints.len() as u64);
+                self.record_sync_response(peer_id, invalid, premints).await;
+            }
+            TreeSyncResponse::Error(err) => {
+                tracing::error!(
+                    peer_id = peer_id.to_string(),
+                    error = err,
+                    "error received to our tree sync request"
+                );
             }
         }
+
+        Ok(())
     }
 
     async fn handle_command(&mut self, command: ControllerCommands) -> eyre::Result<()> {
@@ -202,7 +500,10 @@ impl Controller {
                     .await?;
             }
             ControllerCommands::Broadcast { message, channel } => {
-                match self.validate_and_insert(message.clone()).await {
+                match self
+                    .validate_and_insert(message.clone(), PremintSource::Api)
+                    .await
+                {
                     Ok(_result) => {
                         if let Err(err) = self
                             .swarm_command_sender
@@ -245,18 +546,30 @@ impl Controller {
                         tracing::error!("Error sending db arc response back to command sender");
                     };
                 }
+                DBQuery::TreeRoots(chan) => {
+                    if let Err(_err) = chan.send(Ok(self.store.index().roots())) {
+                        tracing::error!("Error sending tree roots response back to command sender");
+                    };
+                }
             },
             ControllerCommands::ResolveOnchainMint(claim) => {
                 tracing::debug!("Received command to resolve onchain mint, {:?}", claim);
                 // This comes from trusted internal checks run by the running node, so safe to trust
                 // likely want to add some checks here to ensure the claim is valid in future
-                if let Err(err) = self.store.mark_seen_on_chain(claim.clone()).await {
-                    tracing::error!(
-                        error = err.to_string(),
-                        "Error marking premint as seen on chain"
-                    );
-                } else {
-                    tracing::debug!("Marked as seen onchain {:?}", claim.clone());
+                match self.mark_seen_on_chain(claim.clone()).await {
+                    Ok(true) => tracing::debug!("Marked as seen onchain {:?}", claim.clone()),
+                    Ok(false) => {
+                        // peers wouldn't find the premint either, so the claim isn't gossiped
+                        tracing::debug!("No premint for onchain claim {:?}", claim);
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        tracing::error!(
+                            error = err.to_string(),
+                            "Error marking premint as seen on chain"
+                        );
+                        return Ok(());
+                    }
                 }
 
                 if self.config.chain_inclusion_mode == ChainInclusionMode::Check {
@@ -269,24 +582,119 @@ impl Controller {
                     }
                 }
             }
+            ControllerCommands::RevertOnchainMint(claim) => {
+                tracing::info!(
+                    "Received command to revert reorged onchain mint, {:?}",
+                    claim
+                );
+                if let Err(err) = self.revert_seen_on_chain(claim).await {
+                    tracing::error!(
+                        error = err.to_string(),
+                        "Error reverting premint seen on chain"
+                    );
+                }
+            }
             ControllerCommands::Sync => {
                 self.request_sync().await;
             }
+            ControllerCommands::SubscribeEvents { channel } => {
+                if channel.send(self.subscribe_events()).is_err() {
+                    tracing::error!("Error sending event subscription");
+                }
+            }
+            ControllerCommands::ReturnSyncStats { channel } => {
+                if channel.send(self.sync_stats.clone()).is_err() {
+                    tracing::error!("Error sending sync stats back to command sender");
+                }
+            }
+            ControllerCommands::ReconcileResults { results, started } => {
+                self.apply_reconcile_results(results, started).await;
+            }
+            ControllerCommands::ValidatePremint { premint, channel } => {
+                // anyone can ask and rpc rules can take a while, so it's not run on the loop
+                let rules = self.rules.clone();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let results = rules.evaluate_dry_run(&premint, store).await;
+                    if channel.send(results).is_err() {
+                        tracing::error!("Error sending validation results back to command sender");
+                    }
+                });
+            }
+            ControllerCommands::ReturnRules { channel } => {
+                if channel.send(self.rules.summary()).is_err() {
+                    tracing::error!("Error sending rules back to command sender");
+                }
+            }
+            ControllerCommands::UpdateRules { config, channel } => {
+                let result = self.rules.apply_config(&config).map(|_| {
+                    tracing::info!("Rules config updated: {:?}", config);
+                    self.reconcile_rules.apply_toggles(&config);
+                    self.rules.summary()
+                });
+                if channel.send(result).is_err() {
+                    tracing::error!("Error sending updated rules back to command sender");
+                }
+            }
         }
         Ok(())
     }
 
-    async fn validate_and_insert(&self, premint: PremintTypes) -> eyre::Result<Results> {
+    async fn validate_and_insert(
+        &self,
+        premint: PremintTypes,
+        source: PremintSource,
+    ) -> eyre::Result<Results> {
+        let metadata = premint.metadata();
+        let previous = self
+            .store
+            .get_for_id_and_kind(&metadata.id, metadata.kind)
+            .await
+            .ok();
+
+        // peers relay versions we already have all the time, no need to run the rules on those.
+        // not a rules rejection, so the peer isn't penalized for it
+        if let Some(previous) = &previous {
+            let stored_version = previous.metadata().version;
+            if !matches!(source, PremintSource::Api) && stored_version >= metadata.version {
+                return Err(eyre::eyre!(
+                    "Premint {} version {} is not newer than the stored version {}",
+                    metadata.id,
+                    metadata.version,
+                    stored_version
+                ));
+            }
+        }
+
         let evaluation = self.rules.evaluate(&premint, self.store.clone()).await?;
 
         if evaluation.is_accept() {
             tracing::info!(histogram.rules_accepted = 1);
 
             self.store
-                .store(premint)
+                .store(premint.clone())
+                .await
+                .wrap_err("Failed to store premint")?;
+
+            // history is best effort, the premint itself is already stored
+            let rule_results = serde_json::to_value(&evaluation).unwrap_or_default();
+            if let Err(err) = self
+                .store
+                .record_version(&premint, source, rule_results)
                 .await
-                .map(|_r| evaluation)
-                .wrap_err("Failed to store premint")
+            {
+                tracing::error!("Error recording premint version: {:?}", err);
+            }
+
+            self.publish_event(match previous {
+                Some(previous) => PremintEvent::Updated {
+                    premint,
+                    previous_version: previous.metadata().version,
+                },
+                None => PremintEvent::Accepted { premint },
+            });
+
+            Ok(evaluation)
         } else {
             tracing::info!(
                 "Premint {:?} failed validation: {:?}",
@@ -299,6 +707,180 @@ impl Controller {
         }
     }
 
+    /// Returns false if there is no premint of the claim's kind and id, nothing is marked then.
+    /// Types sharing a claim event get a claim each for every mint, only one of them is real.
+    async fn mark_seen_on_chain(&self, claim: InclusionClaim) -> eyre::Result<bool> {
+        // looked up first, the premint is gone once marked if minted premints are pruned
+        let premint = match self
+            .store
+            .get_for_id_and_kind(&claim.premint_id, PremintName(claim.kind.clone()))
+            .await
+        {
+            Ok(premint) => premint,
+            Err(report) => match report.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::RowNotFound) => return Ok(false),
+                _ => return Err(report),
+            },
+        };
+
+        self.store.mark_seen_on_chain(claim.clone()).await?;
+        self.publish_event(PremintEvent::SeenOnChain { premint, claim });
+        Ok(true)
+    }
+
+    /// Re-checks the next batch of active premints against chain state, marking the ones that
+    /// were minted and evicting the ones that are no longer valid. Consecutive batches walk all
+    /// active premints, starting over after the last one.
+    /// The rules run on their own task, so the controller keeps handling events while they call
+    /// the chain, and come back as `ReconcileResults`.
+    async fn reconcile_batch(&mut self) {
+        if self.reconciling {
+            tracing::debug!("Previous reconcile batch is still running");
+            return;
+        }
+
+        let started = Instant::now();
+        let options = QueryOptions {
+            limit: Some(self.config.reconcile_batch_size),
+            cursor: self.reconcile_cursor.take(),
+            ..Default::default()
+        };
+        let page = match list_page_with_options(&self.store.db(), &options).await {
+            Ok(page) => page,
+            Err(err) => {
+                tracing::error!("Error listing premints to reconcile: {:?}", err);
+                return;
+            }
+        };
+        self.reconcile_cursor = page.next_cursor;
+        self.reconciling = true;
+
+        let rules = self.reconcile_rules.clone();
+        let store = self.store.clone();
+        let command_sender = self.command_sender.clone();
+        tokio::spawn(async move {
+            let evaluations = join_all(
+                page.premints
+                    .iter()
+                    .map(|premint| rules.evaluate(premint, store.clone())),
+            )
+            .await;
+
+            let results = page.premints.into_iter().zip(evaluations).collect();
+            if command_sender
+                .send(ControllerCommands::ReconcileResults { results, started })
+                .await
+                .is_err()
+            {
+                tracing::error!("Error sending reconcile results back to controller");
+            }
+        });
+    }
+
+    /// Marks the minted premints of a reconciled batch and evicts the ones that are no longer valid
+    async fn apply_reconcile_results(
+        &mut self,
+        results: Vec<(PremintTypes, eyre::Result<Results>)>,
+        started: Instant,
+    ) {
+        self.reconciling = false;
+
+        let checked = results.len() as u64;
+        let (mut minted, mut evicted) = (0u64, 0u64);
+        for (premint, evaluation) in results {
+            // rules that failed to run, like on rpc errors, leave the premint for the next walk
+            let results = match evaluation {
+                Ok(results) if !results.is_err() => results,
+                Ok(_) => continue,
+                Err(err) => {
+                    tracing::warn!("Error reconciling premint: {:?}", err);
+                    continue;
+                }
+            };
+
+            let rejections = results.rejections();
+            if rejections.is_empty() {
+                continue;
+            }
+
+            let outcome = if rejections
+                .iter()
+                .any(|(rule, _)| rule.ends_with("not_minted"))
+            {
+                minted += 1;
+                self.mark_minted(premint).await
+            } else {
+                evicted += 1;
+                let reason = rejections
+                    .iter()
+                    .map(|(_, reason)| *reason)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.evict(premint, reason).await
+            };
+            if let Err(err) = outcome {
+                tracing::error!("Error removing reconciled premint: {:?}", err);
+            }
+        }
+
+        tracing::info!(
+            counter.reconcile_checked = checked,
+            counter.reconcile_marked_minted = minted,
+            counter.reconcile_evicted = evicted,
+            histogram.reconcile_batch_duration = started.elapsed().as_secs_f64(),
+            "Reconciled premints"
+        );
+    }
+
+    async fn mark_minted(&self, premint: PremintTypes) -> eyre::Result<()> {
+        let metadata = premint.metadata();
+        // the mint transaction isn't known, the claim only identifies the premint
+        self.store
+            .mark_seen_on_chain(InclusionClaim {
+                premint_id: metadata.id,
+                chain_id: metadata.chain_id,
+                tx_hash: Default::default(),
+                log_index: 0,
+                kind: metadata.kind.0,
+            })
+            .await?;
+
+        self.publish_event(PremintEvent::Evicted {
+            premint,
+            reason: "minted".to_string(),
+        });
+        Ok(())
+    }
+
+    async fn evict(&self, premint: PremintTypes, reason: String) -> eyre::Result<()> {
+        tracing::info!("Evicting premint {:?}: {}", premint.metadata().id, reason);
+        // the rules ran on their own task, a newer version may have been stored meanwhile
+        if !self.store.evict(&premint).await? {
+            tracing::info!(
+                "Premint {:?} was updated since it was checked, not evicting",
+                premint.metadata().id
+            );
+            return Ok(());
+        }
+        self.publish_event(PremintEvent::Evicted { premint, reason });
+        Ok(())
+    }
+
+    async fn revert_seen_on_chain(&self, claim: InclusionClaim) -> eyre::Result<()> {
+        if let Some(premint) = self.store.revert_seen_on_chain(&claim).await? {
+            tracing::info!(counter.premints_restored_after_reorg = 1);
+            self.publish_event(PremintEvent::Reorged { premint, claim });
+        }
+        Ok(())
+    }
+
+    fn publish_event(&self, event: PremintEvent) {
+        // only fails when nobody is subscribed
+        if self.events.send(event).is_ok() {
+            tracing::info!(counter.premint_events_published = 1);
+        }
+    }
+
     async fn handle_event_onchain_claim(&self, peer_claim: PeerInclusionClaim) -> eyre::Result<()> {
         match self.config.chain_inclusion_mode {
             ChainInclusionMode::Check | ChainInclusionMode::Verify => {
@@ -314,9 +896,16 @@ impl Controller {
                 match inclusion_claim_correct(&premint, &claim).await {
                     Ok(true) => {
                         self.store.mark_seen_on_chain(claim.clone()).await?;
+                        self.publish_event(PremintEvent::SeenOnChain { premint, claim });
+                        Ok(())
+                    }
+                    Ok(false) => {
+                        tracing::info!("Peer sent an incorrect inclusion claim");
+                        self.report_peer(peer_claim.from_peer_id, PeerOffense::FalseInclusionClaim)
+                            .await;
                         Ok(())
                     }
-                    _ => {
+                    Err(_) => {
                         tracing::info!("Ignoring inclusion claim received from peer");
                         Ok(())
                     }
@@ -328,9 +917,7 @@ impl Controller {
                     .trusted_peers()
                     .contains(&peer_claim.from_peer_id)
                 {
-                    self.store
-                        .mark_seen_on_chain(peer_claim.claim.clone())
-                        .await?;
+                    self.mark_seen_on_chain(peer_claim.claim.clone()).await?;
                     tracing::info!(
                         "Marked premint as seen via claim from trusted peer {:?}",
                         peer_claim
@@ -370,6 +957,14 @@ impl ControllerInterface {
         Ok(recv.await??)
     }
 
+    pub async fn get_tree_roots(&self) -> eyre::Result<Vec<TreeRoot>> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::Query(DBQuery::TreeRoots(snd)))
+            .await?;
+
+        Ok(recv.await??)
+    }
+
     pub async fn get_node_info(&self) -> eyre::Result<MintpoolNodeInfo> {
         let (snd, recv) = oneshot::channel();
         self.send_command(ControllerCommands::ReturnNodeInfo { channel: snd })
@@ -377,6 +972,58 @@ impl ControllerInterface {
         Ok(recv.await?)
     }
 
+    pub async fn get_sync_stats(&self) -> eyre::Result<HashMap<PeerId, PeerSyncStats>> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::ReturnSyncStats { channel: snd })
+            .await?;
+        Ok(recv.await?)
+    }
+
+    pub async fn get_db(&self) -> eyre::Result<AnyPool> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::Query(DBQuery::Direct(snd)))
+            .await?;
+
+        recv.await?
+    }
+
+    /// Live premint events, see `PremintEvent`
+    pub async fn subscribe_events(&self) -> eyre::Result<broadcast::Receiver<PremintEvent>> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::SubscribeEvents { channel: snd })
+            .await?;
+        Ok(recv.await?)
+    }
+
+    /// Rule results for a premint as if it was submitted, without storing or broadcasting it
+    pub async fn validate_premint(&self, premint: PremintTypes) -> eyre::Result<Results> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::ValidatePremint {
+            premint,
+            channel: snd,
+        })
+        .await?;
+        recv.await?
+    }
+
+    pub async fn get_rules(&self) -> eyre::Result<RulesSummary> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::ReturnRules { channel: snd })
+            .await?;
+        Ok(recv.await?)
+    }
+
+    /// Swaps the rules config of the running node, see `RulesEngine::apply_config`
+    pub async fn update_rules(&self, config: RulesConfig) -> eyre::Result<RulesSummary> {
+        let (snd, recv) = oneshot::channel();
+        self.send_command(ControllerCommands::UpdateRules {
+            config,
+            channel: snd,
+        })
+        .await?;
+        recv.await?
+    }
+
     pub async fn get_network_state(&self) -> eyre::Result<NetworkState> {
         let (snd, recv) = oneshot::channel();
         self.send_command(ControllerCommands::ReturnNetworkState { channel: snd })
@@ -384,3 +1031,83 @@ impl ControllerInterface {
         Ok(recv.await?)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::SimplePremint;
+
+    async fn test_controller() -> (Controller, mpsc::Receiver<SwarmCommand>) {
+        let config = Config::test_default();
+        let (swarm_send, swarm_recv) = mpsc::channel(100);
+        let (_event_send, event_recv) = mpsc::channel(100);
+        let (command_send, command_recv) = mpsc::channel(100);
+        let store = PremintStorage::new(&config).await;
+        let rules = RulesEngine::new_with_default_rules(&config);
+
+        let controller = Controller::new(
+            config,
+            swarm_send,
+            event_recv,
+            command_recv,
+            command_send,
+            store,
+            rules,
+        );
+        (controller, swarm_recv)
+    }
+
+    fn premint(token_id: u64) -> PremintTypes {
+        PremintTypes::Simple(SimplePremint::new(
+            1,
+            7777777,
+            Default::default(),
+            token_id,
+            format!("ipfs://{}", token_id),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_sync_of_stored_premints_is_not_penalized() {
+        let (mut controller, mut swarm_recv) = test_controller().await;
+        let premints: Vec<_> = (1..=3).map(premint).collect();
+        for premint in &premints {
+            controller.store.store(premint.clone()).await.unwrap();
+        }
+
+        let peer_id = PeerId::random();
+        controller
+            .handle_event(P2PEvent::SyncResponse {
+                peer_id,
+                query: QueryOptions::default(),
+                premints,
+            })
+            .await;
+
+        let stats = &controller.sync_stats[&peer_id];
+        assert_eq!(stats.accepted, 0);
+        assert_eq!(stats.rejected, 0);
+        assert!(swarm_recv.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gossip_of_stored_premint_is_ignored() {
+        let (mut controller, mut swarm_recv) = test_controller().await;
+        controller.store.store(premint(1)).await.unwrap();
+
+        controller
+            .handle_event(P2PEvent::PremintReceived {
+                premint: premint(1),
+                from_peer_id: PeerId::random(),
+                message_id: MessageId::new(b"1"),
+            })
+            .await;
+
+        match swarm_recv.try_recv() {
+            Ok(SwarmCommand::ReportPremintValidation { evaluation, .. }) => {
+                assert!(matches!(evaluation, Evaluation::Ignore(_)))
+            }
+            other => panic!("Expected a premint validation report, got {:?}", other),
+        }
+    }
+}
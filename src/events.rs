@@ -0,0 +1,106 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::storage::QueryOptions;
+use crate::types::{InclusionClaim, PremintTypes};
+
+/// Events are dropped for subscribers that fall this far behind
+pub const EVENT_CHANNEL_SIZE: usize = 1024;
+
+/// Premint lifecycle events published by the controller as they happen
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PremintEvent {
+    // first version of a premint was accepted
+    Accepted {
+        premint: PremintTypes,
+    },
+    // newer version of a premint we already had was accepted
+    Updated {
+        premint: PremintTypes,
+        previous_version: u64,
+    },
+    // premint was minted on chain
+    SeenOnChain {
+        premint: PremintTypes,
+        claim: InclusionClaim,
+    },
+    // mint of a premint was reorged out and the premint is active again
+    Reorged {
+        premint: PremintTypes,
+        claim: InclusionClaim,
+    },
+    // reconciliation found the premint minted without seeing the mint, or no longer valid
+    Evicted {
+        premint: PremintTypes,
+        reason: String,
+    },
+}
+
+impl PremintEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PremintEvent::Accepted { .. } => "accepted",
+            PremintEvent::Updated { .. } => "updated",
+            PremintEvent::SeenOnChain { .. } => "seen_on_chain",
+            PremintEvent::Reorged { .. } => "reorged",
+            PremintEvent::Evicted { .. } => "evicted",
+        }
+    }
+
+    pub fn premint(&self) -> &PremintTypes {
+        match self {
+            PremintEvent::Accepted { premint }
+            | PremintEvent::Updated { premint, .. }
+            | PremintEvent::SeenOnChain { premint, .. }
+            | PremintEvent::Reorged { premint, .. }
+            | PremintEvent::Evicted { premint, .. } => premint,
+        }
+    }
+
+    /// Checks the premint of the event against the filters of a query
+    pub fn matches(&self, filter: &QueryOptions) -> bool {
+        filter.matches_all(&self.premint().metadata())
+    }
+}
+
+pub fn channel() -> broadcast::Sender<PremintEvent> {
+    let (sender, _) = broadcast::channel(EVENT_CHANNEL_SIZE);
+    sender
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::Address;
+
+    use super::*;
+    use crate::types::SimplePremint;
+
+    #[test]
+    fn test_event_filters() {
+        let premint = SimplePremint::new(2, 7777777, Address::default(), 1, "ipfs://a".to_string());
+        let event = PremintEvent::Updated {
+            premint: PremintTypes::Simple(premint),
+            previous_version: 1,
+        };
+
+        assert!(event.matches(&QueryOptions::default()));
+        assert!(event.matches(&QueryOptions {
+            chain_id: Some(7777777),
+            token_uri_prefix: Some("ipfs://".to_string()),
+            ..Default::default()
+        }));
+        assert!(!event.matches(&QueryOptions {
+            chain_id: Some(8453),
+            ..Default::default()
+        }));
+        assert!(!event.matches(&QueryOptions {
+            min_version: Some(3),
+            ..Default::default()
+        }));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "updated");
+        assert_eq!(json["previous_version"], 1);
+    }
+}
@@ -3,17 +3,23 @@ pub mod chain;
 pub mod chain_list;
 pub mod config;
 pub mod controller;
+pub mod events;
 pub mod metrics;
 pub mod multi_ticker;
 pub mod multiaddr_ext;
+pub mod multicall;
 pub mod p2p;
 pub mod premints;
+pub mod reputation;
 pub mod rules;
+pub mod rules_config;
 pub mod run;
 pub mod stdin;
 pub mod storage;
 pub mod sync;
 pub mod types;
+pub mod view_cache;
+pub mod webhooks;
 
 pub mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
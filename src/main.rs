@@ -1,8 +1,8 @@
 use clap::Parser;
 use mintpool::api;
 use mintpool::metrics::init_metrics_and_logging;
-use mintpool::premints::zora_premint::v2::V2;
 use mintpool::rules::RulesEngine;
+use mintpool::rules_config::RulesConfig;
 use mintpool::run::{start_p2p_services, start_watch_chain};
 use mintpool::stdin::watch_stdin;
 use tokio::signal::unix::{signal, SignalKind};
@@ -17,12 +17,15 @@ async fn main() -> eyre::Result<()> {
 
     let mut rules = RulesEngine::new(&config);
     rules.add_default_rules();
+    if let Some(path) = &config.rules_config_path {
+        rules.apply_config(&RulesConfig::load(path)?)?;
+    }
     let ctl = start_p2p_services(config.clone(), rules).await?;
 
     let router = api::router_with_defaults(&config).merge(metrics_router);
     api::start_api(&config, ctl.clone(), router, true).await?;
 
-    start_watch_chain::<V2>(&config, ctl.clone()).await;
+    start_watch_chain(&config, ctl.clone()).await?;
     tracing::info!(monotonic_counter.chains_watched = 1, "Watching chain");
     if config.interactive {
         watch_stdin(ctl.clone()).await;
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::primitives::{address, Address, Bytes, TxKind};
+use alloy::rpc::types::eth::{TransactionInput, TransactionRequest};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::chain_list::ProviderPool;
+
+/// Multicall3 is deployed at the same address on every chain we support
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// How long the first call of a batch waits for others to join it
+const COALESCE_WINDOW: Duration = Duration::from_millis(10);
+/// Calls per multicall, large batches can run into RPC gas limits for eth_call
+const MAX_BATCH_SIZE: usize = 50;
+
+sol! {
+    #[derive(Debug)]
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        // named Result in Multicall3, renamed so it doesn't shadow the Rust type
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+
+        function getChainId() external view returns (uint256 chainid);
+    }
+}
+
+struct PendingCall {
+    target: Address,
+    input: Bytes,
+    reply: oneshot::Sender<eyre::Result<Bytes>>,
+}
+
+/// Collects view calls made on a chain at the same time into Multicall3 aggregate calls
+pub struct MulticallBatcher {
+    sender: mpsc::UnboundedSender<PendingCall>,
+}
+
+impl MulticallBatcher {
+    pub fn new(rpc: Arc<ProviderPool>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(collect_batches(rpc, receiver));
+
+        Self { sender }
+    }
+
+    pub async fn call(&self, target: Address, input: Bytes) -> eyre::Result<Bytes> {
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .send(PendingCall {
+                target,
+                input,
+                reply,
+            })
+            .map_err(|_| eyre::eyre!("Multicall batcher stopped"))?;
+
+        response.await?
+    }
+}
+
+async fn collect_batches(
+    rpc: Arc<ProviderPool>,
+    mut receiver: mpsc::UnboundedReceiver<PendingCall>,
+) {
+    while let Some(first) = receiver.recv().await {
+        let mut batch = vec![first];
+
+        let window = tokio::time::sleep(COALESCE_WINDOW);
+        tokio::pin!(window);
+        while batch.len() < MAX_BATCH_SIZE {
+            tokio::select! {
+                _ = &mut window => break,
+                call = receiver.recv() => match call {
+                    Some(call) => batch.push(call),
+                    None => break,
+                },
+            }
+        }
+
+        // executed on its own so the next batch can be collected meanwhile
+        tokio::spawn(execute_batch(rpc.clone(), batch));
+    }
+}
+
+async fn execute_batch(rpc: Arc<ProviderPool>, batch: Vec<PendingCall>) {
+    tracing::info!(histogram.multicall_batch_size = batch.len() as u64);
+
+    // nothing to aggregate
+    if batch.len() == 1 {
+        return execute_each(&rpc, batch).await;
+    }
+
+    let calls = batch
+        .iter()
+        .map(|call| IMulticall3::Call3 {
+            target: call.target,
+            allowFailure: true,
+            callData: call.input.clone(),
+        })
+        .collect();
+
+    match aggregate(&rpc, calls).await {
+        Ok(results) if results.len() == batch.len() => {
+            for (call, result) in batch.into_iter().zip(results) {
+                let _ = call.reply.send(call_result(result));
+            }
+        }
+        Ok(_) => {
+            tracing::warn!("Multicall returned a different number of results than calls");
+            execute_each(&rpc, batch).await;
+        }
+        // e.g. Multicall3 isn't deployed, calls still work one by one
+        Err(err) => {
+            tracing::warn!(
+                counter.multicall_fallbacks = 1,
+                "Multicall failed, calling one by one: {:?}",
+                err
+            );
+            execute_each(&rpc, batch).await;
+        }
+    }
+}
+
+async fn aggregate(
+    rpc: &ProviderPool,
+    calls: Vec<IMulticall3::Call3>,
+) -> eyre::Result<Vec<IMulticall3::Call3Result>> {
+    let response = rpc
+        .call(&call_request(
+            MULTICALL3_ADDRESS,
+            IMulticall3::aggregate3Call { calls }.abi_encode().into(),
+        ))
+        .await?;
+
+    let decoded = IMulticall3::aggregate3Call::abi_decode_returns(&response, false)
+        .map_err(|err| eyre::eyre!("Error decoding multicall response: {:?}", err))?;
+    Ok(decoded.returnData)
+}
+
+async fn execute_each(rpc: &ProviderPool, batch: Vec<PendingCall>) {
+    for call in batch {
+        let result = rpc.call(&call_request(call.target, call.input)).await;
+        let _ = call.reply.send(result);
+    }
+}
+
+fn call_result(result: IMulticall3::Call3Result) -> eyre::Result<Bytes> {
+    match result.success {
+        true => Ok(result.returnData),
+        false => Err(eyre::eyre!("execution reverted: {}", result.returnData)),
+    }
+}
+
+fn call_request(to: Address, input: Bytes) -> TransactionRequest {
+    TransactionRequest {
+        to: Some(TxKind::Call(to)),
+        input: TransactionInput::new(input),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::U256;
+    use futures::future::join_all;
+
+    use super::*;
+    use crate::chain_list::CHAINS;
+
+    #[test]
+    fn test_call_result() {
+        let ok = call_result(IMulticall3::Call3Result {
+            success: true,
+            returnData: Bytes::from(vec![1, 2]),
+        });
+        assert_eq!(ok.unwrap(), Bytes::from(vec![1, 2]));
+
+        let reverted = call_result(IMulticall3::Call3Result {
+            success: false,
+            returnData: Bytes::new(),
+        });
+        assert!(reverted.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batched_calls() {
+        let provider = CHAINS.get_rpc(7777777).await.unwrap();
+
+        let input: Bytes = IMulticall3::getChainIdCall {}.abi_encode().into();
+        let calls = (0..5).map(|_| provider.view_call(MULTICALL3_ADDRESS, input.clone()));
+
+        for result in join_all(calls).await {
+            let decoded =
+                IMulticall3::getChainIdCall::abi_decode_returns(&result.unwrap(), false).unwrap();
+            assert_eq!(decoded.chainid, U256::from(7777777));
+        }
+    }
+}
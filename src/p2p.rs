@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::time::Duration;
 
 use eyre::WrapErr;
@@ -6,20 +7,22 @@ use futures_ticker::Ticker;
 use itertools::Itertools;
 use libp2p::autonat::NatStatus;
 use libp2p::futures::StreamExt;
-use libp2p::gossipsub::{IdentTopic, TopicHash, Version};
+use libp2p::gossipsub::{IdentTopic, MessageAcceptance, MessageId, TopicHash, Version};
 use libp2p::identify::Event;
 use libp2p::identity::Keypair;
 use libp2p::kad::store::{MemoryStore, RecordStore};
 use libp2p::kad::{Addresses, ProviderRecord, RecordKey};
 use libp2p::multiaddr::Protocol;
-use libp2p::request_response::{InboundRequestId, Message, ProtocolSupport};
+use libp2p::request_response::{
+    InboundRequestId, Message, OutboundFailure, OutboundRequestId, ProtocolSupport,
+};
 use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use libp2p::swarm::DialError::DialPeerConditionFalse;
 use libp2p::swarm::{ConnectionId, NetworkBehaviour, NetworkInfo, SwarmEvent};
 use libp2p::{
-    autonat, dcutr, gossipsub, kad, noise, relay, request_response, tcp, yamux, Multiaddr, PeerId,
-    StreamProtocol,
+    allow_block_list, autonat, dcutr, gossipsub, kad, noise, relay, request_response, tcp, yamux,
+    Multiaddr, PeerId, StreamProtocol,
 };
 use rand::prelude::SliceRandom;
 use serde::{Deserialize, Serialize};
@@ -30,7 +33,13 @@ use crate::config::Config;
 use crate::controller::{P2PEvent, SwarmCommand};
 use crate::multi_ticker::MultiTicker;
 use crate::multiaddr_ext::MultiaddrExt;
-use crate::storage::QueryOptions;
+use crate::reputation::{PeerOffense, PeerReputation};
+use crate::rules::Evaluation;
+use crate::storage::{cursor_advances, PremintPage, QueryOptions};
+use crate::sync::{
+    query_prefixes, TreeSyncRequest, TreeSyncResponse, MAX_SYNC_PAGES, SUMMARY_DEPTH,
+    SYNC_PAGE_SIZE,
+};
 use crate::types::{
     claims_topic_hashes, InclusionClaim, MintpoolNodeInfo, PeerInclusionClaim, PremintName,
     PremintTypes,
@@ -43,17 +52,20 @@ pub struct MintpoolBehaviour {
     identify: libp2p::identify::Behaviour,
     ping: libp2p::ping::Behaviour,
     request_response: request_response::cbor::Behaviour<QueryOptions, SyncResponse>,
+    tree_sync: request_response::cbor::Behaviour<TreeSyncRequest, TreeSyncResponse>,
     relay: Toggle<relay::Behaviour>,
     relay_client: Toggle<relay::client::Behaviour>,
     relay_manager: Toggle<libp2p_relay_manager::Behaviour>,
     autonat: autonat::Behaviour,
     dcutr: dcutr::Behaviour,
+    blocked_peers: allow_block_list::Behaviour<allow_block_list::BlockedPeers>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 enum SwarmTickers {
     Bootstrap,
     DiscoverGossipPeers,
+    Reputation,
 }
 
 pub struct SwarmController {
@@ -62,9 +74,26 @@ pub struct SwarmController {
     event_sender: tokio::sync::mpsc::Sender<P2PEvent>,
     premint_names: Vec<PremintName>,
     tickers: MultiTicker<SwarmTickers>,
+    // outbound sync requests that haven't been answered yet
+    pending_syncs: HashMap<OutboundRequestId, PendingSync>,
+    reputation: PeerReputation,
     config: Config,
 }
 
+enum PendingSync {
+    Tree {
+        request: TreeSyncRequest,
+        // query to fall back to the query based protocol with, if the peer turns out
+        // not to support tree sync
+        fallback: Option<QueryOptions>,
+    },
+    Query {
+        query: QueryOptions,
+        // pages of this query received so far
+        pages: usize,
+    },
+}
+
 /// Service for managing p2p actions and connections
 impl SwarmController {
     pub fn new(
@@ -109,7 +138,13 @@ impl SwarmController {
                     SwarmTickers::DiscoverGossipPeers,
                     Ticker::new(Duration::from_secs(60)),
                 ),
+                (
+                    SwarmTickers::Reputation,
+                    Ticker::new(Duration::from_secs(60)),
+                ),
             ]),
+            pending_syncs: HashMap::new(),
+            reputation: PeerReputation::new(config),
         }
     }
 
@@ -140,17 +175,33 @@ impl SwarmController {
                 let gossipsub_config = gossipsub::ConfigBuilder::default()
                     .heartbeat_interval(Duration::from_secs(10))
                     .validation_mode(gossipsub::ValidationMode::Strict)
+                    // messages are only forwarded once we've validated them,
+                    // see report_message_validation
+                    .validate_messages()
                     .protocol_id("/mintpool/0.1.0", Version::V1_1)
                     .message_id_fn(gossipsub_message_id)
                     .build()
                     .expect("valid config");
 
-                let gossipsub = gossipsub::Behaviour::new(
+                let mut gossipsub = gossipsub::Behaviour::new(
                     gossipsub::MessageAuthenticity::Signed(key.clone()),
                     gossipsub_config,
                 )
                 .expect("valid config");
 
+                // only application scores from PeerReputation are used, colocation is disabled
+                // since nodes commonly run several peers behind the same IP
+                gossipsub
+                    .with_peer_score(
+                        gossipsub::PeerScoreParams {
+                            app_specific_weight: 1.0,
+                            ip_colocation_factor_weight: 0.0,
+                            ..Default::default()
+                        },
+                        gossipsub::PeerScoreThresholds::default(),
+                    )
+                    .expect("valid peer score config");
+
                 let identify = libp2p::identify::Behaviour::new(libp2p::identify::Config::new(
                     "mintpool/0.1.0".to_string(),
                     public_key,
@@ -158,12 +209,23 @@ impl SwarmController {
 
                 let ping = libp2p::ping::Behaviour::new(libp2p::ping::Config::new());
 
+                let sync_config = request_response::Config::default()
+                    .with_request_timeout(Duration::from_secs(config.sync_timeout_secs));
+
                 let request_response = request_response::cbor::Behaviour::new(
                     [(
                         StreamProtocol::new("/mintpool-sync/1"),
                         ProtocolSupport::Full,
                     )],
-                    request_response::Config::default(),
+                    sync_config.clone(),
+                );
+
+                let tree_sync = request_response::cbor::Behaviour::new(
+                    [(
+                        StreamProtocol::new("/mintpool-tree-sync/1"),
+                        ProtocolSupport::Full,
+                    )],
+                    sync_config,
                 );
 
                 let autonat = autonat::Behaviour::new(
@@ -206,11 +268,13 @@ impl SwarmController {
                     identify,
                     ping,
                     request_response,
+                    tree_sync,
                     relay,
                     relay_client,
                     relay_manager,
                     autonat,
                     dcutr,
+                    blocked_peers: Default::default(),
                 }
             })?
             .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(60)))
@@ -275,6 +339,7 @@ impl SwarmController {
                                 b.kad.get_providers(Self::topic_to_record_key(topic));
                             });
                         }
+                        (SwarmTickers::Reputation, _) => self.update_reputation(),
                     }
                 }
             }
@@ -318,6 +383,98 @@ impl SwarmController {
                 }
             }
             SwarmCommand::Sync { query } => self.do_sync(query).await,
+            SwarmCommand::TreeSync { peer_id, request } => {
+                let id = self.send_tree_sync_request(peer_id, request, None);
+                tracing::debug!(request_id = id.to_string(), "sent tree sync request");
+            }
+            SwarmCommand::ReportPeer { peer_id, offense } => {
+                self.penalize_peer(peer_id, offense);
+            }
+            SwarmCommand::ReportPremintValidation {
+                message_id,
+                propagation_source,
+                evaluation,
+            } => {
+                let acceptance = match evaluation {
+                    Evaluation::Accept => MessageAcceptance::Accept,
+                    Evaluation::Ignore(_) => MessageAcceptance::Ignore,
+                    Evaluation::Reject(_) => {
+                        self.penalize_peer(propagation_source, PeerOffense::RuleRejection);
+                        MessageAcceptance::Reject
+                    }
+                };
+                self.report_message_validation(&message_id, propagation_source, acceptance);
+            }
+        }
+    }
+
+    /// Tells gossipsub whether a received message should be forwarded to the mesh
+    fn report_message_validation(
+        &mut self,
+        message_id: &MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    ) {
+        tracing::debug!(
+            message_id = message_id.to_string(),
+            acceptance = format!("{:?}", acceptance),
+            "reporting message validation result"
+        );
+
+        if let Err(err) = self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .report_message_validation_result(message_id, &propagation_source, acceptance)
+        {
+            tracing::warn!("Error reporting message validation result: {:?}", err);
+        }
+    }
+
+    fn reject_unparsable_message(&mut self, message_id: &MessageId, propagation_source: PeerId) {
+        self.penalize_peer(propagation_source, PeerOffense::UnparsableMessage);
+        self.report_message_validation(message_id, propagation_source, MessageAcceptance::Reject);
+    }
+
+    /// Lowers the score of a misbehaving peer, banning it if it crosses the threshold
+    fn penalize_peer(&mut self, peer_id: PeerId, offense: PeerOffense) {
+        let score = self.reputation.penalize(peer_id, offense);
+        tracing::info!(
+            peer_id = peer_id.to_string(),
+            offense = format!("{:?}", offense),
+            score = score,
+            "penalized peer"
+        );
+        tracing::info!(counter.peer_penalties = 1);
+
+        let b = self.swarm.behaviour_mut();
+        b.gossipsub.set_application_score(&peer_id, score);
+
+        if self.reputation.should_ban(&peer_id) {
+            tracing::warn!(peer_id = peer_id.to_string(), "banning peer");
+            tracing::info!(counter.peers_banned = 1);
+
+            self.reputation.ban(peer_id);
+            b.gossipsub.blacklist_peer(&peer_id);
+            b.blocked_peers.block_peer(peer_id);
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+    }
+
+    /// Lets scores recover and lifts expired bans
+    fn update_reputation(&mut self) {
+        let b = self.swarm.behaviour_mut();
+
+        for (peer_id, score) in self.reputation.decay() {
+            b.gossipsub.set_application_score(&peer_id, score);
+        }
+
+        for peer_id in self.reputation.expire_bans(std::time::Instant::now()) {
+            tracing::info!(peer_id = peer_id.to_string(), "lifting ban of peer");
+            tracing::info!(counter.peers_banned = -1);
+
+            b.gossipsub.remove_blacklisted_peer(&peer_id);
+            b.blocked_peers.unblock_peer(peer_id);
         }
     }
 
@@ -426,6 +583,15 @@ impl SwarmController {
                 }
             }
 
+            SwarmEvent::Behaviour(MintpoolBehaviourEvent::TreeSync(event)) => {
+                match self.handle_tree_sync_event(event).await {
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::error!("Error handling tree sync event: {:?}", err);
+                    }
+                }
+            }
+
             SwarmEvent::Behaviour(MintpoolBehaviourEvent::RelayClient(event)) => {
                 match self.handle_relay_client_event(event).await {
                     Ok(_) => {}
@@ -560,23 +726,44 @@ impl SwarmController {
             gossipsub::Event::Message {
                 message,
                 propagation_source,
-                ..
+                message_id,
             } => {
                 let msg = String::from_utf8_lossy(&message.data);
                 // Handle announcements
                 if message.topic == registry_topic.hash() {
                     tracing::info!("New peer: {:?}", msg);
-                    let addr: Multiaddr = msg
-                        .to_string()
-                        .parse()
-                        .wrap_err(format!("invalid address found from announce: {}", msg))?;
+                    let addr: Multiaddr = match msg.to_string().parse() {
+                        Ok(addr) => addr,
+                        Err(err) => {
+                            self.reject_unparsable_message(&message_id, propagation_source);
+                            return Err(err)
+                                .wrap_err(format!("invalid address found from announce: {}", msg));
+                        }
+                    };
+                    self.report_message_validation(
+                        &message_id,
+                        propagation_source,
+                        MessageAcceptance::Accept,
+                    );
 
                     self.safe_dial(addr).await;
                     tracing::info!(histogram.peer_announced = 1);
                 // Handle inclusion claims
                 } else if claims_topic_hashes(self.premint_names.clone()).contains(&message.topic) {
-                    let claim = serde_json::from_str::<InclusionClaim>(&msg)
-                        .wrap_err("Error parsing inclusion claim")?;
+                    let claim = match serde_json::from_str::<InclusionClaim>(&msg) {
+                        Ok(claim) => claim,
+                        Err(err) => {
+                            self.reject_unparsable_message(&message_id, propagation_source);
+                            return Err(err).wrap_err("Error parsing inclusion claim");
+                        }
+                    };
+                    // claims are checked against the chain later on, which is too slow to hold
+                    // up propagation for. False claims are penalized when we find out.
+                    self.report_message_validation(
+                        &message_id,
+                        propagation_source,
+                        MessageAcceptance::Accept,
+                    );
 
                     self.event_sender
                         .send(P2PEvent::MintSeenOnchain(PeerInclusionClaim {
@@ -592,8 +779,14 @@ impl SwarmController {
                         Ok(premint) => {
                             let id = premint.metadata().id;
                             tracing::info!(id = id, "Received new premint");
+                            // the controller reports back whether to propagate it once the
+                            // rules have been evaluated
                             self.event_sender
-                                .send(P2PEvent::PremintReceived(premint.clone()))
+                                .send(P2PEvent::PremintReceived {
+                                    premint: premint.clone(),
+                                    from_peer_id: propagation_source,
+                                    message_id,
+                                })
                                 .await
                                 .wrap_err("failed to send premint event")?;
                             tracing::debug!("premint event sent: {:?}", premint);
@@ -601,6 +794,7 @@ impl SwarmController {
                         }
                         Err(err) => {
                             tracing::error!("Error parsing premint: {:?}", err);
+                            self.reject_unparsable_message(&message_id, propagation_source);
                         }
                     }
                 }
@@ -736,25 +930,99 @@ impl SwarmController {
     }
 
     async fn do_sync(&mut self, query: QueryOptions) {
-        // select random peer
+        // select random peers, syncing with several at once means a single slow
+        // or misbehaving peer can't hold up the sync
         let state = self.make_network_state();
 
-        let peer_id = state
+        let peers: Vec<PeerId> = state
             .gossipsub_peers
-            .choose(&mut rand::thread_rng())
-            .cloned();
-
-        if let Some(peer_id) = peer_id {
-            let id = self
-                .swarm
-                .behaviour_mut()
-                .request_response
-                .send_request(&peer_id, query);
+            .choose_multiple(&mut rand::thread_rng(), self.config.sync_peers)
+            .cloned()
+            .collect();
 
-            tracing::info!(request_id = id.to_string(), "sent sync request");
-        } else {
+        if peers.is_empty() {
             tracing::info!("No peers to sync with");
+            return;
         }
+
+        let prefixes = query_prefixes(&query, &self.premint_names);
+        let query = QueryOptions {
+            limit: Some(SYNC_PAGE_SIZE),
+            ..query
+        };
+
+        for peer_id in peers {
+            for (i, prefix) in prefixes.iter().enumerate() {
+                let request = TreeSyncRequest::Summary {
+                    prefix: prefix.clone(),
+                    depth: SUMMARY_DEPTH,
+                };
+
+                // one fallback per peer is enough, the query covers all prefixes
+                let fallback = (i == 0).then(|| query.clone());
+                let id = self.send_tree_sync_request(peer_id, request, fallback);
+
+                tracing::info!(
+                    request_id = id.to_string(),
+                    peer_id = peer_id.to_string(),
+                    "sent sync request"
+                );
+            }
+        }
+    }
+
+    fn send_tree_sync_request(
+        &mut self,
+        peer_id: PeerId,
+        request: TreeSyncRequest,
+        fallback: Option<QueryOptions>,
+    ) -> OutboundRequestId {
+        let id = self
+            .swarm
+            .behaviour_mut()
+            .tree_sync
+            .send_request(&peer_id, request.clone());
+        self.pending_syncs
+            .insert(id, PendingSync::Tree { request, fallback });
+        id
+    }
+
+    fn send_query_sync_request(
+        &mut self,
+        peer_id: PeerId,
+        query: QueryOptions,
+        pages: usize,
+    ) -> OutboundRequestId {
+        let id = self
+            .swarm
+            .behaviour_mut()
+            .request_response
+            .send_request(&peer_id, query.clone());
+        self.pending_syncs
+            .insert(id, PendingSync::Query { query, pages });
+        id
+    }
+
+    async fn handle_sync_failure(
+        &mut self,
+        peer_id: PeerId,
+        request_id: OutboundRequestId,
+        error: OutboundFailure,
+    ) -> eyre::Result<()> {
+        tracing::warn!(
+            request_id = request_id.to_string(),
+            peer_id = peer_id.to_string(),
+            error = error.to_string(),
+            "sync request failed"
+        );
+
+        if let OutboundFailure::Timeout = error {
+            self.event_sender
+                .send(P2PEvent::SyncTimeout { peer_id })
+                .await?;
+        }
+
+        Ok(())
     }
 
     async fn handle_request_response_event(
@@ -762,7 +1030,7 @@ impl SwarmController {
         event: request_response::Event<QueryOptions, SyncResponse>,
     ) -> eyre::Result<()> {
         match event {
-            request_response::Event::Message { message, .. } => match message {
+            request_response::Event::Message { peer, message } => match message {
                 Message::Request {
                     request_id,
                     request,
@@ -783,10 +1051,63 @@ impl SwarmController {
                         request_id = request_id.to_string(),
                         "received response for sync"
                     );
+                    let (query, pages) = match self.pending_syncs.remove(&request_id) {
+                        Some(PendingSync::Query { query, pages }) => (query, pages + 1),
+                        _ => {
+                            tracing::warn!(
+                                request_id = request_id.to_string(),
+                                "received sync response for unknown request"
+                            );
+                            return Ok(());
+                        }
+                    };
+
                     match response {
                         SyncResponse::Premints(premints) => {
                             self.event_sender
-                                .send(P2PEvent::SyncResponse { premints })
+                                .send(P2PEvent::SyncResponse {
+                                    peer_id: peer,
+                                    query,
+                                    premints,
+                                })
+                                .await?;
+                        }
+                        SyncResponse::Page(page) => {
+                            // ask for the next page before handing this one over
+                            match page.next_cursor {
+                                Some(cursor)
+                                    if !cursor_advances(query.cursor.as_deref(), &cursor) =>
+                                {
+                                    // a cursor that doesn't move on would keep us paging forever
+                                    tracing::warn!(
+                                        peer_id = peer.to_string(),
+                                        "sync cursor did not advance, stopped paging"
+                                    );
+                                    self.penalize_peer(peer, PeerOffense::InvalidSyncResponse);
+                                }
+                                Some(_) if pages >= MAX_SYNC_PAGES => {
+                                    tracing::info!(
+                                        peer_id = peer.to_string(),
+                                        pages = pages,
+                                        "reached sync page limit, rest is left for the next sync"
+                                    );
+                                }
+                                Some(cursor) => {
+                                    let next = QueryOptions {
+                                        cursor: Some(cursor),
+                                        ..query.clone()
+                                    };
+                                    self.send_query_sync_request(peer, next, pages);
+                                }
+                                None => {}
+                            }
+
+                            self.event_sender
+                                .send(P2PEvent::SyncResponse {
+                                    peer_id: peer,
+                                    query,
+                                    premints: page.premints,
+                                })
                                 .await?;
                         }
                         SyncResponse::Error(err) => {
@@ -799,11 +1120,91 @@ impl SwarmController {
                     }
                 }
             },
+            request_response::Event::OutboundFailure {
+                peer,
+                request_id,
+                error,
+            } => {
+                self.pending_syncs.remove(&request_id);
+                self.handle_sync_failure(peer, request_id, error).await?;
+            }
             other => tracing::info!("mintpool sync request/response event: {:?}", other),
         }
         Ok(())
     }
 
+    async fn handle_tree_sync_event(
+        &mut self,
+        event: request_response::Event<TreeSyncRequest, TreeSyncResponse>,
+    ) -> eyre::Result<()> {
+        match event {
+            request_response::Event::Message { peer, message } => match message {
+                Message::Request {
+                    request_id,
+                    request,
+                    channel,
+                } => {
+                    let resp = self.make_tree_sync_response(request_id, request).await;
+                    self.swarm
+                        .behaviour_mut()
+                        .tree_sync
+                        .send_response(channel, resp)
+                        .map_err(|e| eyre::eyre!("Error sending response: {:?}", e))?;
+                }
+                Message::Response {
+                    request_id,
+                    response,
+                } => {
+                    tracing::debug!(
+                        request_id = request_id.to_string(),
+                        "received response for tree sync"
+                    );
+
+                    let request = match self.pending_syncs.remove(&request_id) {
+                        Some(PendingSync::Tree { request, .. }) => request,
+                        _ => {
+                            tracing::warn!(
+                                request_id = request_id.to_string(),
+                                "received tree sync response for unknown request"
+                            );
+                            return Ok(());
+                        }
+                    };
+
+                    self.event_sender
+                        .send(P2PEvent::TreeSyncResponse {
+                            peer_id: peer,
+                            request,
+                            response,
+                        })
+                        .await?;
+                }
+            },
+            request_response::Event::OutboundFailure {
+                peer,
+                request_id,
+                error,
+            } => match (self.pending_syncs.remove(&request_id), error) {
+                (
+                    Some(PendingSync::Tree {
+                        fallback: Some(query),
+                        ..
+                    }),
+                    OutboundFailure::UnsupportedProtocols,
+                ) => {
+                    let id = self.send_query_sync_request(peer, query, 0);
+                    tracing::info!(
+                        request_id = id.to_string(),
+                        "peer does not support tree sync, sent query sync request"
+                    );
+                }
+                (_, error) => self.handle_sync_failure(peer, request_id, error).await?,
+            },
+            other => tracing::info!("mintpool tree sync request/response event: {:?}", other),
+        }
+        Ok(())
+    }
+
     async fn handle_relay_client_event(&mut self, event: relay::client::Event) -> eyre::Result<()> {
         match event {
             relay::client::Event::ReservationReqAccepted { relay_peer_id, .. } => {
@@ -870,14 +1271,20 @@ impl SwarmController {
     async fn make_sync_response(
         &mut self,
         request_id: InboundRequestId,
-        request: QueryOptions,
+        mut request: QueryOptions,
     ) -> SyncResponse {
         tracing::info!(
             request_id = request_id.to_string(),
             "processing request for sync"
         );
+
+        // peers that don't ask for a limit don't know about paging, so they get everything at once
+        let paged = request.limit.is_some();
+        request.limit = request.limit.map(|limit| limit.min(SYNC_PAGE_SIZE));
+
         match self.make_sync_response_query(request).await {
-            Ok(premints) => SyncResponse::Premints(premints),
+            Ok(page) if paged => SyncResponse::Page(page),
+            Ok(page) => SyncResponse::Premints(page.premints),
             Err(err) => {
                 tracing::error!(
                     request_id = request_id.to_string(),
@@ -889,11 +1296,43 @@ impl SwarmController {
         }
     }
 
+    // Makes a Response for a tree sync request from another node
+    async fn make_tree_sync_response(
+        &mut self,
+        request_id: InboundRequestId,
+        request: TreeSyncRequest,
+    ) -> TreeSyncResponse {
+        let (snd, recv) = tokio::sync::oneshot::channel();
+        let result = async {
+            self.event_sender
+                .send(P2PEvent::TreeSyncRequest {
+                    request,
+                    channel: snd,
+                })
+                .await
+                .map_err(|_| eyre::eyre!("Controller error"))?;
+            recv.await.map_err(|_| eyre::eyre!("Channel error"))?
+        }
+        .await;
+
+        match result {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::error!(
+                    request_id = request_id.to_string(),
+                    error = err.to_string(),
+                    "error processing tree sync request"
+                );
+                TreeSyncResponse::Error(err.to_string())
+            }
+        }
+    }
+
     // inner function to make propagating errors that occur during query easier to work with
     async fn make_sync_response_query(
         &mut self,
         request: QueryOptions,
-    ) -> eyre::Result<Vec<PremintTypes>> {
+    ) -> eyre::Result<PremintPage> {
         let (snd, recv) = tokio::sync::oneshot::channel();
         self.event_sender
             .send(P2PEvent::SyncRequest {
@@ -944,6 +1383,8 @@ pub struct NetworkState {
 pub enum SyncResponse {
     Premints(Vec<PremintTypes>),
     Error(String),
+    // only sent in response to queries with a limit
+    Page(PremintPage),
 }
 
 fn announce_topic() -> gossipsub::IdentTopic {
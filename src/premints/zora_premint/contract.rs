@@ -7,10 +7,82 @@ pub static PREMINT_FACTORY_ADDR: Address = address!("7777773606e7e46C8Ba8B98C08f
 // we need to use separate namespaces for each premint version,
 // because they all need to have the type names for the signatures
 // to calculate correctly
+// zora1155PremintExecutor_erc20_1.json still has the legacy `isValidSignature` and `Preminted`,
+// whose V1 `TokenCreationConfig` clashes with the erc20 struct of the same name. So only what we
+// use is bound here, with the signatures of that abi.
 sol! {
     #[derive(Debug, Serialize, Deserialize, PartialEq)]
-    IZoraPremintERC20V1,
-    "src/premints/zora_premint/zora1155PremintExecutor_erc20_1.json"
+    interface IZoraPremintERC20V1 {
+        struct ContractCreationConfig {
+            address contractAdmin;
+            string contractURI;
+            string contractName;
+        }
+
+        struct TokenCreationConfig {
+            string tokenURI;
+            uint256 maxSupply;
+            uint32 royaltyBPS;
+            address payoutRecipient;
+            address createReferral;
+            address erc20Minter;
+            uint64 mintStart;
+            uint64 mintDuration;
+            uint64 maxTokensPerAddress;
+            address currency;
+            uint256 pricePerToken;
+        }
+
+        struct CreatorAttribution {
+            TokenCreationConfig tokenConfig;
+            uint32 uid;
+            uint32 version;
+            bool deleted;
+        }
+
+        struct MintArguments {
+            address mintRecipient;
+            string mintComment;
+            address[] mintRewardsRecipients;
+        }
+
+        struct PremintResult {
+            address contractAddress;
+            uint256 tokenId;
+            bool createdNewContract;
+        }
+
+        event PremintedV2(
+            address indexed contractAddress,
+            uint256 indexed tokenId,
+            bool indexed createdNewContract,
+            uint32 uid,
+            address minter,
+            uint256 quantityMinted
+        );
+
+        function getContractAddress(ContractCreationConfig calldata contractConfig) external view returns (address);
+
+        function isAuthorizedToCreatePremint(
+            address signer,
+            address premintContractConfigContractAdmin,
+            address contractAddress
+        ) external view returns (bool isAuthorized);
+
+        function premintStatus(address contractAddress, uint32 uid) external view returns (bool contractCreated, uint256 tokenIdForPremint);
+
+        function supportedPremintSignatureVersions(address contractAddress) external view returns (string[] memory versions);
+
+        function premintErc20V1(
+            ContractCreationConfig calldata contractConfig,
+            CreatorAttribution calldata premintConfig,
+            bytes calldata signature,
+            uint256 quantityToMint,
+            MintArguments calldata mintArguments,
+            address firstMinter,
+            address signerContract
+        ) external returns (PremintResult memory result);
+    }
 }
 
 sol! {
@@ -3,7 +3,7 @@ use alloy::primitives::Address;
 
 use crate::premints::zora_premint::contract::IZoraPremintERC20V1;
 
-use crate::rules::Rule;
+use crate::rules::{Rule, RuleStage};
 use crate::storage::Reader;
 use crate::types::PremintTypes;
 
@@ -22,14 +22,15 @@ impl Default for IZoraPremintERC20V1::TokenCreationConfig {
         Self {
             tokenURI: Default::default(),
             maxSupply: Default::default(),
-            maxTokensPerAddress: Default::default(),
-            pricePerToken: Default::default(),
+            royaltyBPS: Default::default(),
+            payoutRecipient: Default::default(),
+            createReferral: Default::default(),
+            erc20Minter: Default::default(),
             mintStart: Default::default(),
             mintDuration: Default::default(),
-            royaltyBPS: Default::default(),
-            fixedPriceMinter: Default::default(),
-            royaltyMintSchedule: Default::default(),
-            royaltyRecipient: Default::default(),
+            maxTokensPerAddress: Default::default(),
+            currency: Default::default(),
+            pricePerToken: Default::default(),
         }
     }
 }
@@ -52,18 +53,115 @@ implement_zora_premint_traits!(
     "ERC20_1"
 );
 
-pub fn all_v2_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
+pub fn all_erc20v1_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
     vec![
         typed_rule!(
             PremintTypes::ZoraERC20V1,
-            ERC20V1::is_authorized_to_create_premint
+            ERC20V1::is_authorized_to_create_premint,
+            RuleStage::Rpc
         ),
         typed_rule!(PremintTypes::ZoraERC20V1, ERC20V1::is_valid_signature),
         typed_rule!(PremintTypes::ZoraERC20V1, ERC20V1::is_chain_supported),
-        typed_rule!(PremintTypes::ZoraERC20V1, ERC20V1::not_minted),
         typed_rule!(
             PremintTypes::ZoraERC20V1,
-            ERC20V1::premint_version_supported
+            ERC20V1::not_minted,
+            RuleStage::Rpc
+        ),
+        typed_rule!(
+            PremintTypes::ZoraERC20V1,
+            ERC20V1::premint_version_supported,
+            RuleStage::Rpc
         ),
     ]
 }
+
+/// Rules whose result depends on chain state and can change after a premint was accepted
+pub fn chain_state_erc20v1_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
+    vec![
+        typed_rule!(
+            PremintTypes::ZoraERC20V1,
+            ERC20V1::is_authorized_to_create_premint,
+            RuleStage::Rpc
+        ),
+        typed_rule!(
+            PremintTypes::ZoraERC20V1,
+            ERC20V1::not_minted,
+            RuleStage::Rpc
+        ),
+        typed_rule!(
+            PremintTypes::ZoraERC20V1,
+            ERC20V1::premint_version_supported,
+            RuleStage::Rpc
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::{address, U256};
+    use alloy::signers::wallet::LocalWallet;
+    use alloy::signers::Signer;
+    use alloy::sol_types::SolStruct;
+
+    use crate::rules::Evaluation::{Accept, Reject};
+    use crate::rules::RuleContext;
+
+    use super::*;
+
+    async fn signed_premint(wallet: &LocalWallet) -> ERC20V1 {
+        let mut premint = ERC20V1 {
+            collection_address: address!("a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2"),
+            chain_id: 7777777,
+            ..Default::default()
+        };
+        premint.collection.contractAdmin = wallet.address();
+        premint.collection.contractName = "ERC20 collection".to_string();
+        premint.premint.uid = 1;
+        premint.premint.version = 1;
+        premint.premint.tokenConfig.tokenURI = "ipfs://token".to_string();
+        premint.premint.tokenConfig.maxSupply = U256::from(100);
+        premint.premint.tokenConfig.pricePerToken = U256::from(1_000_000);
+        premint.premint.tokenConfig.currency = address!("1111111111111111111111111111111111111111");
+
+        let hash = premint
+            .premint
+            .eip712_signing_hash(&premint.eip712_domain());
+        let signature = wallet.sign_hash(&hash).await.unwrap();
+        premint.signature = const_hex::encode_prefixed(signature.as_bytes());
+        premint
+    }
+
+    #[tokio::test]
+    async fn test_is_valid_signature() {
+        let wallet = LocalWallet::random();
+        let premint = signed_premint(&wallet).await;
+        let context = RuleContext::test_default().await;
+
+        match ERC20V1::is_valid_signature(&premint, &context).await {
+            Ok(Accept) => {}
+            other => panic!("Should accept, got {:?}", other),
+        }
+
+        // the erc20 config is part of what's signed
+        let mut tampered = premint.clone();
+        tampered.premint.tokenConfig.currency = Address::default();
+        match ERC20V1::is_valid_signature(&tampered, &context).await {
+            Ok(Reject(_)) => {}
+            other => panic!("Should reject, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_metadata() {
+        let premint = ERC20V1 {
+            chain_id: 7777777,
+            ..Default::default()
+        };
+        let metadata = crate::types::Premint::metadata(&premint);
+        assert_eq!(metadata.kind.0, "zora_premint_erc20v1");
+        assert_eq!(
+            metadata.id,
+            format!("7777777:{:?}:0", premint.collection_address)
+        );
+    }
+}
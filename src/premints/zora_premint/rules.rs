@@ -16,6 +16,7 @@ macro_rules! zora_premint_rules {
                 premintContractConfigContractAdmin: premint.collection.contractAdmin,
             };
 
+            // not cached, permissions can be granted or revoked at any time
             let result = $crate::chain::view_contract_call(
                 call,
                 rpc,
@@ -69,7 +70,7 @@ macro_rules! zora_premint_rules {
                 contractAddress: premint.collection_address,
             };
 
-            let result = $crate::chain::view_contract_call(
+            let result = $crate::chain::cached_view_contract_call(
                 call,
                 rpc,
                 $crate::premints::zora_premint::contract::PREMINT_FACTORY_ADDR,
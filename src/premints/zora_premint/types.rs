@@ -60,16 +60,15 @@ macro_rules! implement_zora_premint_traits {
                 }
             }
 
-            fn check_filter(chain_id: u64) -> Option<alloy::rpc::types::eth::Filter> {
+            fn claim_event(chain_id: u64) -> Option<$crate::types::ClaimEvent> {
                 let supported_chains = [7777777, 8453]; // TODO: add the rest here and enable testnet mode
                 if !supported_chains.contains(&chain_id) {
                     return None;
                 }
-                Some(
-                    alloy::rpc::types::eth::Filter::new()
-                        .address($crate::premints::zora_premint::contract::PREMINT_FACTORY_ADDR)
-                        .event(<$namespace::PremintedV2 as alloy::sol_types::SolEvent>::SIGNATURE),
-                )
+                Some($crate::types::ClaimEvent {
+                    address: $crate::premints::zora_premint::contract::PREMINT_FACTORY_ADDR,
+                    signature: <$namespace::PremintedV2 as alloy::sol_types::SolEvent>::SIGNATURE_HASH,
+                })
             }
 
             fn map_claim(
@@ -112,7 +111,7 @@ macro_rules! implement_zora_premint_traits {
                             claim.tx_hash == tx.transaction_hash,
                             claim.log_index == log.log_index.unwrap_or_default(),
                             claim.premint_id == Self::event_to_guid(chain_id, &event),
-                            claim.kind == *"zora_premint_v2",
+                            claim.kind == *$kind,
                             claim.chain_id == chain_id,
                             self.collection_address == event.contractAddress,
                             self.premint.uid == event.uid,
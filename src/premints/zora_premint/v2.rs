@@ -3,7 +3,7 @@ use alloy::primitives::Address;
 
 use crate::premints::zora_premint::contract::IZoraPremintV2;
 
-use crate::rules::Rule;
+use crate::rules::{Rule, RuleStage};
 use crate::storage::Reader;
 use crate::types::PremintTypes;
 
@@ -49,11 +49,36 @@ implement_zora_premint_traits!(IZoraPremintV2, V2, "zora_premint_v2", "2");
 
 pub fn all_v2_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
     vec![
-        typed_rule!(PremintTypes::ZoraV2, V2::is_authorized_to_create_premint),
+        typed_rule!(
+            PremintTypes::ZoraV2,
+            V2::is_authorized_to_create_premint,
+            RuleStage::Rpc
+        ),
         typed_rule!(PremintTypes::ZoraV2, V2::is_valid_signature),
         typed_rule!(PremintTypes::ZoraV2, V2::is_chain_supported),
-        typed_rule!(PremintTypes::ZoraV2, V2::not_minted),
-        typed_rule!(PremintTypes::ZoraV2, V2::premint_version_supported),
+        typed_rule!(PremintTypes::ZoraV2, V2::not_minted, RuleStage::Rpc),
+        typed_rule!(
+            PremintTypes::ZoraV2,
+            V2::premint_version_supported,
+            RuleStage::Rpc
+        ),
+    ]
+}
+
+/// Rules whose result depends on chain state and can change after a premint was accepted
+pub fn chain_state_v2_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
+    vec![
+        typed_rule!(
+            PremintTypes::ZoraV2,
+            V2::is_authorized_to_create_premint,
+            RuleStage::Rpc
+        ),
+        typed_rule!(PremintTypes::ZoraV2, V2::not_minted, RuleStage::Rpc),
+        typed_rule!(
+            PremintTypes::ZoraV2,
+            V2::premint_version_supported,
+            RuleStage::Rpc
+        ),
     ]
 }
 
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use libp2p::PeerId;
+
+use crate::config::Config;
+
+/// Misbehaviour we can attribute to a peer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PeerOffense {
+    // premint failed the rules engine
+    RuleRejection,
+    // message could not be parsed as a premint or claim
+    UnparsableMessage,
+    // inclusion claim that didn't hold up when checked against the chain
+    FalseInclusionClaim,
+    // sync response with premints or summaries we didn't ask for
+    InvalidSyncResponse,
+}
+
+impl PeerOffense {
+    pub fn penalty(&self) -> f64 {
+        match self {
+            // rules can differ between nodes, so a rejection alone isn't much of a signal
+            PeerOffense::RuleRejection => 5.0,
+            PeerOffense::UnparsableMessage => 20.0,
+            PeerOffense::InvalidSyncResponse => 20.0,
+            PeerOffense::FalseInclusionClaim => 50.0,
+        }
+    }
+}
+
+/// Scores below this are rounded to 0 and forgotten when decaying
+const MIN_SCORE: f64 = 0.1;
+
+/// Tracks application level scores of peers.
+/// Scores are always <= 0, every offense lowers the score and scores recover over time.
+/// Peers whose score drops below the ban threshold get banned for a while.
+pub struct PeerReputation {
+    scores: HashMap<PeerId, f64>,
+    banned: HashMap<PeerId, Instant>,
+    ban_threshold: f64,
+    ban_duration: Duration,
+    // fraction of the score that is kept on every decay
+    decay: f64,
+}
+
+impl PeerReputation {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            scores: HashMap::new(),
+            banned: HashMap::new(),
+            ban_threshold: config.peer_ban_threshold,
+            ban_duration: Duration::from_secs(60 * config.peer_ban_minutes),
+            decay: 0.9,
+        }
+    }
+
+    /// Lowers the score of a peer, returns the new score
+    pub fn penalize(&mut self, peer_id: PeerId, offense: PeerOffense) -> f64 {
+        let score = self.scores.entry(peer_id).or_default();
+        *score -= offense.penalty();
+        *score
+    }
+
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.scores.get(peer_id).copied().unwrap_or_default()
+    }
+
+    pub fn should_ban(&self, peer_id: &PeerId) -> bool {
+        !self.is_banned(peer_id) && self.score(peer_id) <= self.ban_threshold
+    }
+
+    pub fn ban(&mut self, peer_id: PeerId) {
+        self.banned
+            .insert(peer_id, Instant::now() + self.ban_duration);
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned.contains_key(peer_id)
+    }
+
+    pub fn banned_peers(&self) -> Vec<PeerId> {
+        self.banned.keys().cloned().collect()
+    }
+
+    /// Moves all scores towards 0, returns the updated scores
+    pub fn decay(&mut self) -> Vec<(PeerId, f64)> {
+        let decay = self.decay;
+        self.scores.values_mut().for_each(|score| {
+            *score *= decay;
+            if score.abs() < MIN_SCORE {
+                *score = 0.0
+            }
+        });
+
+        let updated = self.scores.iter().map(|(p, s)| (*p, *s)).collect();
+        self.scores.retain(|_, score| *score != 0.0);
+        updated
+    }
+
+    /// Lifts bans that have run out, returns the peers that were unbanned.
+    /// Unbanned peers start over with a clean score.
+    pub fn expire_bans(&mut self, now: Instant) -> Vec<PeerId> {
+        let expired: Vec<PeerId> = self
+            .banned
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+
+        for peer_id in &expired {
+            self.banned.remove(peer_id);
+            self.scores.remove(peer_id);
+        }
+
+        expired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ban_after_repeated_offenses() {
+        let config = Config {
+            peer_ban_threshold: -100.0,
+            peer_ban_minutes: 1,
+            ..Config::test_default()
+        };
+        let mut reputation = PeerReputation::new(&config);
+        let peer_id = PeerId::random();
+
+        assert_eq!(
+            reputation.penalize(peer_id, PeerOffense::FalseInclusionClaim),
+            -50.0
+        );
+        assert!(!reputation.should_ban(&peer_id));
+
+        reputation.penalize(peer_id, PeerOffense::FalseInclusionClaim);
+        assert!(reputation.should_ban(&peer_id));
+
+        reputation.ban(peer_id);
+        assert!(reputation.is_banned(&peer_id));
+        assert!(!reputation.should_ban(&peer_id));
+
+        // not expired yet
+        assert!(reputation.expire_bans(Instant::now()).is_empty());
+
+        let later = Instant::now() + Duration::from_secs(61);
+        assert_eq!(reputation.expire_bans(later), vec![peer_id]);
+        assert!(!reputation.is_banned(&peer_id));
+        assert_eq!(reputation.score(&peer_id), 0.0);
+    }
+
+    #[test]
+    fn test_scores_recover() {
+        let mut reputation = PeerReputation::new(&Config::test_default());
+        let peer_id = PeerId::random();
+
+        reputation.penalize(peer_id, PeerOffense::RuleRejection);
+        let updated = reputation.decay();
+        assert_eq!(updated, vec![(peer_id, -4.5)]);
+
+        for _ in 0..100 {
+            reputation.decay();
+        }
+        assert_eq!(reputation.score(&peer_id), 0.0);
+        assert!(reputation.decay().is_empty());
+    }
+}
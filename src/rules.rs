@@ -1,6 +1,8 @@
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use futures::future::join_all;
@@ -9,6 +11,7 @@ use serde::{Serialize, Serializer};
 
 use crate::chain_list::{ChainListProvider, CHAINS};
 use crate::config::Config;
+use crate::rules_config::RulesConfig;
 use crate::storage::PremintStorage;
 use crate::storage::Reader;
 use crate::types::PremintTypes;
@@ -65,6 +68,8 @@ macro_rules! ignore {
 pub struct RuleResult {
     pub rule_name: &'static str,
     pub result: eyre::Result<Evaluation>,
+    // zero for rules that were skipped
+    pub duration: Duration,
 }
 
 impl Clone for RuleResult {
@@ -75,6 +80,7 @@ impl Clone for RuleResult {
                 Ok(e) => Ok(e.clone()),
                 Err(e) => Err(eyre::eyre!(e.to_string())),
             },
+            duration: self.duration,
         }
     }
 }
@@ -85,7 +91,7 @@ impl Serialize for RuleResult {
         S: Serializer,
     {
         serializer
-            .serialize_struct("RuleResult", 3)
+            .serialize_struct("RuleResult", 4)
             .and_then(|mut s| {
                 match self.result {
                     Ok(ref result) => {
@@ -98,6 +104,7 @@ impl Serialize for RuleResult {
                     }
                 }
                 s.serialize_field("rule_name", &self.rule_name)?;
+                s.serialize_field("duration_ms", &(self.duration.as_millis() as u64))?;
                 s.end()
             })
     }
@@ -132,6 +139,27 @@ impl Results {
     pub fn is_err(&self) -> bool {
         self.0.iter().any(|r| r.result.is_err())
     }
+
+    /// Whether the premint was rejected for more than going over a quota or not being newer than
+    /// the stored version. Quotas are counted from what each node stores and peers relay what we
+    /// already have all the time, neither means the peer is misbehaving.
+    pub fn blames_sender(&self) -> bool {
+        self.rejections().iter().any(|(rule_name, _)| {
+            *rule_name != general::RateLimit::RULE_NAME
+                && *rule_name != general::VERSION_IS_HIGHER_RULE_NAME
+        })
+    }
+
+    /// Names of the rules that rejected, with their reasons
+    pub fn rejections(&self) -> Vec<(&'static str, &str)> {
+        self.0
+            .iter()
+            .filter_map(|r| match r.result {
+                Ok(Evaluation::Reject(ref reason)) => Some((r.rule_name, reason.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 impl Error for Results {}
@@ -192,6 +220,15 @@ impl RuleContext<PremintStorage> {
     }
 }
 
+/// When a rule runs during evaluation. Offline rules only look at the premint and storage,
+/// RPC rules call the chain and only run once no offline rule rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleStage {
+    Offline,
+    Rpc,
+}
+
 #[async_trait]
 pub trait Rule<T: Reader>: Send + Sync {
     async fn check(
@@ -200,6 +237,9 @@ pub trait Rule<T: Reader>: Send + Sync {
         context: &RuleContext<T>,
     ) -> eyre::Result<Evaluation>;
     fn rule_name(&self) -> &'static str;
+    fn stage(&self) -> RuleStage {
+        RuleStage::Offline
+    }
 }
 
 #[macro_export]
@@ -252,7 +292,10 @@ macro_rules! metadata_rule {
 
 #[macro_export]
 macro_rules! typed_rule {
-    ($t:path, $fn:path) => {{
+    ($t:path, $fn:path) => {
+        $crate::typed_rule!($t, $fn, $crate::rules::RuleStage::Offline)
+    };
+    ($t:path, $fn:path, $stage:expr) => {{
         struct TypedRule;
 
         #[async_trait::async_trait]
@@ -271,6 +314,10 @@ macro_rules! typed_rule {
             fn rule_name(&self) -> &'static str {
                 concat!(stringify!($t), "::", stringify!($fn))
             }
+
+            fn stage(&self) -> $crate::rules::RuleStage {
+                $stage
+            }
         }
 
         std::boxed::Box::new(TypedRule {})
@@ -279,7 +326,53 @@ macro_rules! typed_rule {
 
 pub struct RulesEngine<T: Reader = PremintStorage> {
     rules: Vec<Box<dyn Rule<T>>>,
+    // swapped as a whole when the rules config changes at runtime, evaluations that are
+    // already running keep the one they started with
+    configured: RwLock<Arc<ConfiguredRules<T>>>,
+    stats: Mutex<HashMap<&'static str, RuleStats>>,
     use_rpc: bool,
+    rule_timeout: Duration,
+    rate_limit: general::RateLimit,
+}
+
+struct ConfiguredRules<T: Reader> {
+    config: RulesConfig,
+    rules: Vec<Box<dyn Rule<T>>>,
+}
+
+impl<T: Reader> ConfiguredRules<T> {
+    fn new(config: RulesConfig) -> Self {
+        Self {
+            rules: config.declarative_rules(),
+            config,
+        }
+    }
+}
+
+/// Outcomes of a rule since the node started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RuleStats {
+    pub evaluated: u64,
+    pub rejected: u64,
+    pub errors: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleStatus {
+    pub rule_name: &'static str,
+    pub stage: RuleStage,
+    pub enabled: bool,
+    // set up by the rules config rather than built in
+    pub declarative: bool,
+    #[serde(flatten)]
+    pub stats: RuleStats,
+}
+
+/// Every rule of an engine with the rules config in use
+#[derive(Debug, Clone, Serialize)]
+pub struct RulesSummary {
+    pub rules: Vec<RuleStatus>,
+    pub config: RulesConfig,
 }
 
 pub fn all_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
@@ -287,6 +380,18 @@ pub fn all_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
 
     rules.append(&mut general::all_rules());
     rules.append(&mut crate::premints::zora_premint::v2::all_v2_rules());
+    rules.append(&mut crate::premints::zora_premint::erc20v1::all_erc20v1_rules());
+
+    rules
+}
+
+/// Rules that depend on chain state rather than just the premint, used to re-check premints
+/// that were already accepted
+pub fn chain_state_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
+    let mut rules: Vec<Box<dyn Rule<T>>> = Vec::new();
+
+    rules.append(&mut crate::premints::zora_premint::v2::chain_state_v2_rules());
+    rules.append(&mut crate::premints::zora_premint::erc20v1::chain_state_erc20v1_rules());
 
     rules
 }
@@ -295,7 +400,11 @@ impl<T: Reader> RulesEngine<T> {
     pub fn new(config: &Config) -> Self {
         RulesEngine {
             rules: vec![],
+            configured: RwLock::new(Arc::new(ConfiguredRules::new(RulesConfig::default()))),
+            stats: Default::default(),
             use_rpc: config.enable_rpc,
+            rule_timeout: Duration::from_millis(config.rule_timeout_ms),
+            rate_limit: general::RateLimit::new(config),
         }
     }
 
@@ -305,6 +414,7 @@ impl<T: Reader> RulesEngine<T> {
 
     pub fn add_default_rules(&mut self) {
         self.rules.extend(all_rules());
+        self.rules.push(Box::new(self.rate_limit));
     }
 
     pub fn new_with_default_rules(config: &Config) -> Self {
@@ -313,7 +423,94 @@ impl<T: Reader> RulesEngine<T> {
         engine
     }
 
+    /// Switches rules on or off and replaces the declarative rules with those of a rules config,
+    /// also while the node is running. Fails on rule names that don't match any rule, those are
+    /// most likely typos.
+    pub fn apply_config(&self, rules_config: &RulesConfig) -> eyre::Result<()> {
+        let configured = ConfiguredRules::new(rules_config.clone());
+
+        let names: HashSet<&str> = self
+            .rules
+            .iter()
+            .chain(&configured.rules)
+            .map(|rule| rule.rule_name())
+            .collect();
+        if let Some(unknown) = rules_config
+            .rules
+            .keys()
+            .find(|name| !names.contains(name.as_str()))
+        {
+            return Err(eyre::eyre!("Unknown rule in rules config: {}", unknown));
+        }
+
+        *self.configured.write().unwrap() = Arc::new(configured);
+        Ok(())
+    }
+
+    /// Switches rules on or off as in a rules config, without adding its declarative rules. For
+    /// engines running a subset of the rules, like the one reconciling stored premints, which
+    /// shouldn't fail on the names of rules they don't have.
+    pub fn apply_toggles(&self, rules_config: &RulesConfig) {
+        *self.configured.write().unwrap() = Arc::new(ConfiguredRules {
+            config: rules_config.clone(),
+            rules: vec![],
+        });
+    }
+
+    pub fn config(&self) -> RulesConfig {
+        self.configured.read().unwrap().config.clone()
+    }
+
+    pub fn summary(&self) -> RulesSummary {
+        let configured = self.configured.read().unwrap().clone();
+        let stats = self.stats.lock().unwrap();
+
+        let status = |rule: &dyn Rule<T>, declarative: bool| RuleStatus {
+            rule_name: rule.rule_name(),
+            stage: rule.stage(),
+            enabled: configured.config.is_enabled(rule.rule_name()),
+            declarative,
+            stats: stats.get(rule.rule_name()).copied().unwrap_or_default(),
+        };
+
+        RulesSummary {
+            rules: self
+                .rules
+                .iter()
+                .map(|rule| status(rule.as_ref(), false))
+                .chain(
+                    configured
+                        .rules
+                        .iter()
+                        .map(|rule| status(rule.as_ref(), true)),
+                )
+                .collect(),
+            config: configured.config.clone(),
+        }
+    }
+
+    pub fn new_with_chain_state_rules(config: &Config) -> Self {
+        let mut engine = Self::new(config);
+        engine.rules.extend(chain_state_rules());
+        engine
+    }
+
     pub async fn evaluate(&self, item: &PremintTypes, store: T) -> eyre::Result<Results> {
+        self.evaluate_with_stats(item, store, true).await
+    }
+
+    /// Evaluates without counting towards the rule stats, for premints that are only being
+    /// validated and won't be stored
+    pub async fn evaluate_dry_run(&self, item: &PremintTypes, store: T) -> eyre::Result<Results> {
+        self.evaluate_with_stats(item, store, false).await
+    }
+
+    async fn evaluate_with_stats(
+        &self,
+        item: &PremintTypes,
+        store: T,
+        record_stats: bool,
+    ) -> eyre::Result<Results> {
         let metadata = item.metadata();
         let existing = match store.get_for_id_and_kind(&metadata.id, metadata.kind).await {
             Ok(existing) => Some(existing),
@@ -342,31 +539,133 @@ impl<T: Reader> RulesEngine<T> {
             },
         );
 
-        let results: Vec<_> = self
+        let start = Instant::now();
+        let configured = self.configured.read().unwrap().clone();
+        let rules: Vec<&dyn Rule<T>> = self
             .rules
             .iter()
-            .map(|rule| rule.check(&item, &context))
+            .chain(&configured.rules)
+            .filter(|rule| configured.config.is_enabled(rule.rule_name()))
+            .map(|rule| rule.as_ref())
             .collect();
-        let all_checks = join_all(results).await;
 
-        Ok(Results(
-            all_checks
-                .into_iter()
-                .zip(self.rules.iter())
-                .map(|(result, rule)| RuleResult {
-                    rule_name: rule.rule_name(),
-                    result,
-                })
-                .collect(),
-        ))
+        // cheap rules first, no need to ask the chain about premints they already rejected
+        let mut results = self
+            .run_stage(&rules, RuleStage::Offline, item, &context, record_stats)
+            .await;
+        let rejected = results
+            .iter()
+            .any(|r| matches!(r.result, Ok(Evaluation::Reject(_))));
+
+        match rejected {
+            true => results.extend(skip_stage(&rules, RuleStage::Rpc)),
+            false => results.extend(
+                self.run_stage(&rules, RuleStage::Rpc, item, &context, record_stats)
+                    .await,
+            ),
+        }
+
+        tracing::info!(
+            histogram.rules_evaluation_duration = start.elapsed().as_millis() as u64,
+            short_circuited = rejected
+        );
+
+        Ok(Results(results))
+    }
+
+    // runs the stage's rules concurrently, each bounded by the rule timeout
+    async fn run_stage(
+        &self,
+        rules: &[&dyn Rule<T>],
+        stage: RuleStage,
+        item: &PremintTypes,
+        context: &RuleContext<T>,
+        record_stats: bool,
+    ) -> Vec<RuleResult> {
+        let checks = rules
+            .iter()
+            .filter(|rule| rule.stage() == stage)
+            .map(|rule| self.run_rule(*rule, item, context));
+
+        let results = join_all(checks).await;
+        if record_stats {
+            for result in &results {
+                self.count(result.rule_name, &result.result);
+            }
+        }
+        results
+    }
+
+    async fn run_rule(
+        &self,
+        rule: &dyn Rule<T>,
+        item: &PremintTypes,
+        context: &RuleContext<T>,
+    ) -> RuleResult {
+        let start = Instant::now();
+        let result = match tokio::time::timeout(self.rule_timeout, rule.check(item, context)).await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!(
+                    counter.rule_timeouts = 1,
+                    rule = rule.rule_name(),
+                    "Rule timed out"
+                );
+                Err(eyre::eyre!(
+                    "Rule timed out after {}ms",
+                    self.rule_timeout.as_millis()
+                ))
+            }
+        };
+        let duration = start.elapsed();
+        tracing::info!(
+            histogram.rule_duration = duration.as_millis() as u64,
+            rule = rule.rule_name()
+        );
+
+        RuleResult {
+            rule_name: rule.rule_name(),
+            result,
+            duration,
+        }
+    }
+
+    fn count(&self, rule_name: &'static str, result: &eyre::Result<Evaluation>) {
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(rule_name).or_default();
+
+        stats.evaluated += 1;
+        match result {
+            Ok(Evaluation::Reject(_)) => stats.rejected += 1,
+            Err(_) => stats.errors += 1,
+            _ => {}
+        }
     }
 }
 
+fn skip_stage<T: Reader>(rules: &[&dyn Rule<T>], stage: RuleStage) -> Vec<RuleResult> {
+    rules
+        .iter()
+        .filter(|rule| rule.stage() == stage)
+        .map(|rule| RuleResult {
+            rule_name: rule.rule_name(),
+            result: Ok(Evaluation::Ignore(
+                "Skipped, already rejected by an earlier rule".to_string(),
+            )),
+            duration: Duration::ZERO,
+        })
+        .collect()
+}
+
 mod general {
+    use async_trait::async_trait;
+
+    use crate::config::Config;
     use crate::rules::Evaluation::Accept;
     use crate::rules::{Evaluation, Rule, RuleContext};
     use crate::storage::Reader;
-    use crate::types::PremintMetadata;
+    use crate::types::{PremintMetadata, PremintTypes};
 
     pub fn all_rules<T: Reader>() -> Vec<Box<dyn Rule<T>>> {
         vec![
@@ -443,6 +742,8 @@ mod general {
         }
     }
 
+    pub const VERSION_IS_HIGHER_RULE_NAME: &str = "Metadata::version_is_higher";
+
     pub async fn version_is_higher<T: Reader>(
         meta: &PremintMetadata,
         context: &RuleContext<T>,
@@ -462,6 +763,75 @@ mod general {
             }
         }
     }
+
+    /// Caps the new premints a signer and a collection can add in a sliding window, counted from
+    /// storage so premints are limited the same whether they come from the api, gossip or sync
+    #[derive(Debug, Clone, Copy)]
+    pub struct RateLimit {
+        window_secs: u64,
+        // 0 means no limit
+        max_per_signer: u64,
+        max_per_collection: u64,
+    }
+
+    impl RateLimit {
+        pub const RULE_NAME: &'static str = "Metadata::within_rate_limit";
+
+        pub fn new(config: &Config) -> Self {
+            Self {
+                window_secs: config.rate_limit_window_secs,
+                max_per_signer: config.max_premints_per_signer,
+                max_per_collection: config.max_premints_per_collection,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl<T: Reader> Rule<T> for RateLimit {
+        async fn check(
+            &self,
+            item: &PremintTypes,
+            context: &RuleContext<T>,
+        ) -> eyre::Result<Evaluation> {
+            if self.max_per_signer == 0 && self.max_per_collection == 0 {
+                return ignore!("No premint quotas set");
+            }
+            // new versions replace a premint rather than adding one
+            if context.existing.is_some() {
+                return ignore!("Existing premint");
+            }
+
+            let meta = item.metadata();
+            let since = chrono::Utc::now() - chrono::Duration::seconds(self.window_secs as i64);
+            let counts = context
+                .storage
+                .count_recent(meta.signer, meta.collection_address, since)
+                .await?;
+
+            if self.max_per_signer > 0 && counts.by_signer >= self.max_per_signer {
+                reject!(
+                    "Signer {} already added {} premints in the last {}s",
+                    meta.signer,
+                    counts.by_signer,
+                    self.window_secs
+                )
+            } else if self.max_per_collection > 0 && counts.by_collection >= self.max_per_collection
+            {
+                reject!(
+                    "Collection {} already got {} premints in the last {}s",
+                    meta.collection_address,
+                    counts.by_collection,
+                    self.window_secs
+                )
+            } else {
+                Ok(Accept)
+            }
+        }
+
+        fn rule_name(&self) -> &'static str {
+            Self::RULE_NAME
+        }
+    }
 }
 
 #[cfg(test)]
@@ -519,6 +889,21 @@ mod test {
         Ok(Accept)
     }
 
+    async fn rejecting_typed_rule<T: Reader>(
+        _item: &SimplePremint,
+        _context: &RuleContext<T>,
+    ) -> eyre::Result<Evaluation> {
+        reject!("Rejected offline")
+    }
+
+    async fn slow_typed_rule<T: Reader>(
+        _item: &SimplePremint,
+        _context: &RuleContext<T>,
+    ) -> eyre::Result<Evaluation> {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        Ok(Accept)
+    }
+
     #[tokio::test]
     async fn test_simple_rule() {
         let context = RuleContext::test_default().await;
@@ -544,6 +929,245 @@ mod test {
         assert!(result.is_accept());
     }
 
+    #[tokio::test]
+    async fn test_rejections() {
+        let (mut engine, storage) = test_rules_engine().await;
+        engine.add_rule(rule!(simple_rule));
+        engine.add_rule(rule!(conditional_rule));
+
+        let premint = SimplePremint::new(1, 7777777, Default::default(), 1, "ipfs://a".into());
+        let result = engine
+            .evaluate(&PremintTypes::Simple(premint), storage)
+            .await
+            .expect("Evaluation should not fail");
+
+        assert!(result.is_reject());
+        assert_eq!(
+            result.rejections(),
+            vec![("conditional_rule", "Chain ID is not default")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offline_rejection_skips_rpc_rules() {
+        let (mut engine, storage) = test_rules_engine().await;
+        engine.add_rule(typed_rule!(
+            PremintTypes::Simple,
+            slow_typed_rule,
+            RuleStage::Rpc
+        ));
+        engine.add_rule(typed_rule!(PremintTypes::Simple, rejecting_typed_rule));
+
+        let start = Instant::now();
+        let result = engine
+            .evaluate(&PremintTypes::Simple(Default::default()), storage)
+            .await
+            .expect("Evaluation should not fail");
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(result.is_reject());
+        // offline rules come first, the rpc rule is reported as skipped
+        assert_eq!(
+            result.0[0].rule_name,
+            "PremintTypes::Simple::rejecting_typed_rule"
+        );
+        assert!(matches!(result.0[1].result, Ok(Evaluation::Ignore(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rule_timeout() {
+        let config = Config {
+            rule_timeout_ms: 100,
+            ..Config::test_default()
+        };
+        let storage = PremintStorage::new(&config).await;
+        let mut engine = RulesEngine::new(&config);
+        engine.add_rule(rule!(simple_rule));
+        engine.add_rule(typed_rule!(
+            PremintTypes::Simple,
+            slow_typed_rule,
+            RuleStage::Rpc
+        ));
+
+        let start = Instant::now();
+        let result = engine
+            .evaluate(&PremintTypes::Simple(Default::default()), storage)
+            .await
+            .expect("Evaluation should not fail");
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(result.is_err());
+        assert!(result.to_string().contains("Rule timed out after 100ms"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let config = Config {
+            max_premints_per_signer: 2,
+            ..Config::test_default()
+        };
+        let storage = PremintStorage::new(&config).await;
+        let engine = RulesEngine::new_with_default_rules(&config);
+
+        let premint = |version: u64, token_id: u64| {
+            let uri = format!("ipfs://{}", token_id);
+            PremintTypes::Simple(SimplePremint::new(
+                version,
+                0,
+                Default::default(),
+                token_id,
+                uri,
+            ))
+        };
+
+        for token_id in 1..=2 {
+            let result = engine
+                .evaluate(&premint(1, token_id), storage.clone())
+                .await
+                .unwrap();
+            assert!(result.is_accept());
+            storage.store(premint(1, token_id)).await.unwrap();
+        }
+
+        let result = engine
+            .evaluate(&premint(1, 3), storage.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            result
+                .rejections()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            vec!["Metadata::within_rate_limit"]
+        );
+        assert!(!result.blames_sender());
+
+        // a signer over quota can still update the premints it has
+        let result = engine
+            .evaluate(&premint(2, 1), storage.clone())
+            .await
+            .unwrap();
+        assert!(result.is_accept());
+    }
+
+    #[tokio::test]
+    async fn test_apply_rules_config() {
+        let (mut engine, storage) = test_rules_engine().await;
+        engine.add_default_rules();
+
+        let unknown = RulesConfig::from_toml("[rules]\nnot_a_rule = false").unwrap();
+        assert!(engine.apply_config(&unknown).is_err());
+
+        let rules_config = RulesConfig::from_toml(
+            r#"
+            uri_schemes = ["ipfs://"]
+
+            [rules]
+            "Metadata::token_uri_length" = false
+            "#,
+        )
+        .unwrap();
+        engine.apply_config(&rules_config).unwrap();
+
+        let summary = engine.summary();
+        let status = |name: &str| summary.rules.iter().find(|r| r.rule_name == name).cloned();
+        assert!(!status("Metadata::token_uri_length").unwrap().enabled);
+        assert!(status("Config::uri_scheme_allowed").unwrap().declarative);
+
+        // an empty uri is no longer caught by the length rule, but by the scheme rule
+        let result = engine
+            .evaluate(&PremintTypes::Simple(Default::default()), storage.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            result
+                .rejections()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            vec!["Config::uri_scheme_allowed"]
+        );
+
+        let stats = |summary: &RulesSummary, name: &str| {
+            summary
+                .rules
+                .iter()
+                .find(|r| r.rule_name == name)
+                .map(|r| r.stats)
+                .unwrap_or_default()
+        };
+        let summary = engine.summary();
+        assert_eq!(
+            stats(&summary, "Config::uri_scheme_allowed"),
+            RuleStats {
+                evaluated: 1,
+                rejected: 1,
+                errors: 0
+            }
+        );
+        assert_eq!(stats(&summary, "Metadata::token_uri_length").evaluated, 0);
+
+        // dry runs don't count
+        let result = engine
+            .evaluate_dry_run(&PremintTypes::Simple(Default::default()), storage.clone())
+            .await
+            .unwrap();
+        assert!(result.is_reject());
+        assert_eq!(
+            stats(&engine.summary(), "Config::uri_scheme_allowed").evaluated,
+            1
+        );
+
+        // swapping the config back switches the length rule on and drops the declarative one
+        engine.apply_config(&RulesConfig::default()).unwrap();
+        let summary = engine.summary();
+        assert!(status_enabled(&summary, "Metadata::token_uri_length"));
+        assert!(!summary
+            .rules
+            .iter()
+            .any(|r| r.rule_name == "Config::uri_scheme_allowed"));
+
+        let result = engine
+            .evaluate(&PremintTypes::Simple(Default::default()), storage)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.rejections(),
+            vec![("Metadata::token_uri_length", "Token URI is empty")]
+        );
+    }
+
+    #[test]
+    fn test_apply_toggles() {
+        let engine =
+            RulesEngine::<PremintStorage>::new_with_chain_state_rules(&Config::test_default());
+
+        let rules_config = RulesConfig::from_toml(
+            r#"
+            uri_schemes = ["ipfs://"]
+
+            [rules]
+            "Metadata::token_uri_length" = false
+            "V2::premint_version_supported" = false
+            "#,
+        )
+        .unwrap();
+        engine.apply_toggles(&rules_config);
+
+        let summary = engine.summary();
+        assert!(!status_enabled(&summary, "V2::premint_version_supported"));
+        assert!(status_enabled(&summary, "V2::not_minted"));
+        assert!(!summary.rules.iter().any(|r| r.declarative));
+    }
+
+    fn status_enabled(summary: &RulesSummary, name: &str) -> bool {
+        summary
+            .rules
+            .iter()
+            .any(|r| r.rule_name == name && r.enabled)
+    }
+
     #[tokio::test]
     async fn test_typed_rules_engine() {
         let (mut engine, storage) = test_rules_engine().await;
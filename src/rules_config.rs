@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::rules::Evaluation::{Accept, Ignore, Reject};
+use crate::rules::{Evaluation, Rule, RuleContext};
+use crate::storage::Reader;
+use crate::types::PremintTypes;
+
+/// Rules setup of a node, so curated pools can be run without writing rules in Rust.
+/// Switches built-in rules on or off and sets up declarative rules, settings left out add no rule.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    // built-in rules by rule name, rules that aren't listed stay on
+    pub rules: BTreeMap<String, bool>,
+    pub collections: AddressList,
+    pub signers: AddressList,
+    // ex: ["ipfs://", "ar://"]
+    pub uri_schemes: Vec<String>,
+    // in wei, only checked for premints priced in ETH
+    pub max_price_per_token: Option<U256>,
+    // in seconds, open ended mints (a duration of 0) are over any cap
+    pub max_mint_duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AddressList {
+    // only these are accepted, unless empty
+    pub allow: Vec<Address>,
+    pub deny: Vec<Address>,
+}
+
+impl AddressList {
+    fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    fn check(&self, address: Address, what: &str) -> Evaluation {
+        if self.deny.contains(&address) {
+            Reject(format!("{} {} is denied", what, address))
+        } else if !self.allow.is_empty() && !self.allow.contains(&address) {
+            Reject(format!("{} {} is not allowed", what, address))
+        } else {
+            Accept
+        }
+    }
+}
+
+impl RulesConfig {
+    /// Reads a TOML or JSON rules config, picked by the file extension
+    pub fn load(path: &str) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed to read rules config {}: {}", path, e))?;
+
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&contents),
+            Some("json") => Self::from_json(&contents),
+            _ => Err(eyre::eyre!(
+                "Rules config {} should be a .toml or .json file",
+                path
+            )),
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> eyre::Result<Self> {
+        toml::from_str(contents).map_err(|e| eyre::eyre!("Invalid rules config: {}", e))
+    }
+
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        serde_json::from_str(contents).map_err(|e| eyre::eyre!("Invalid rules config: {}", e))
+    }
+
+    pub fn is_enabled(&self, rule_name: &str) -> bool {
+        self.rules.get(rule_name).copied().unwrap_or(true)
+    }
+
+    /// Rules for the declarative settings that are set
+    pub fn declarative_rules<T: Reader>(&self) -> Vec<Box<dyn Rule<T>>> {
+        let config = Arc::new(self.clone());
+        let rule = |name: &'static str, check: Check| -> Box<dyn Rule<T>> {
+            Box::new(ConfiguredRule {
+                name,
+                config: config.clone(),
+                check,
+            })
+        };
+
+        let mut rules = Vec::new();
+        if !self.collections.is_empty() {
+            rules.push(rule("Config::collection_allowed", collection_allowed));
+        }
+        if !self.signers.is_empty() {
+            rules.push(rule("Config::signer_allowed", signer_allowed));
+        }
+        if !self.uri_schemes.is_empty() {
+            rules.push(rule("Config::uri_scheme_allowed", uri_scheme_allowed));
+        }
+        if self.max_price_per_token.is_some() {
+            rules.push(rule("Config::price_within_cap", price_within_cap));
+        }
+        if self.max_mint_duration.is_some() {
+            rules.push(rule(
+                "Config::mint_duration_within_cap",
+                mint_duration_within_cap,
+            ));
+        }
+        rules
+    }
+}
+
+type Check = fn(&RulesConfig, &PremintTypes) -> Evaluation;
+
+struct ConfiguredRule {
+    name: &'static str,
+    config: Arc<RulesConfig>,
+    check: Check,
+}
+
+#[async_trait]
+impl<T: Reader> Rule<T> for ConfiguredRule {
+    async fn check(
+        &self,
+        item: &PremintTypes,
+        _context: &RuleContext<T>,
+    ) -> eyre::Result<Evaluation> {
+        Ok((self.check)(&self.config, item))
+    }
+
+    fn rule_name(&self) -> &'static str {
+        self.name
+    }
+}
+
+fn collection_allowed(config: &RulesConfig, item: &PremintTypes) -> Evaluation {
+    config
+        .collections
+        .check(item.metadata().collection_address, "Collection")
+}
+
+fn signer_allowed(config: &RulesConfig, item: &PremintTypes) -> Evaluation {
+    config.signers.check(item.metadata().signer, "Signer")
+}
+
+fn uri_scheme_allowed(config: &RulesConfig, item: &PremintTypes) -> Evaluation {
+    let uri = item.metadata().uri;
+    let scheme = uri.split_once(':').map(|(scheme, _)| scheme);
+
+    // schemes may be written as "ipfs", "ipfs:" or "ipfs://"
+    let allowed = config
+        .uri_schemes
+        .iter()
+        .map(|s| s.trim_end_matches("//").trim_end_matches(':'))
+        .any(|s| Some(s) == scheme);
+
+    match allowed {
+        true => Accept,
+        false => Reject(format!("Token URI scheme of {} is not allowed", uri)),
+    }
+}
+
+fn price_within_cap(config: &RulesConfig, item: &PremintTypes) -> Evaluation {
+    let max = config.max_price_per_token.unwrap_or(U256::MAX);
+    let price = match item {
+        PremintTypes::ZoraV2(premint) => U256::from(premint.premint.tokenConfig.pricePerToken),
+        _ => return Ignore("Not priced in ETH".to_string()),
+    };
+
+    match price <= max {
+        true => Accept,
+        false => Reject(format!("Price per token {} is over {}", price, max)),
+    }
+}
+
+fn mint_duration_within_cap(config: &RulesConfig, item: &PremintTypes) -> Evaluation {
+    let max = config.max_mint_duration.unwrap_or(u64::MAX);
+    let duration = match item {
+        PremintTypes::ZoraV2(premint) => premint.premint.tokenConfig.mintDuration,
+        PremintTypes::ZoraERC20V1(premint) => premint.premint.tokenConfig.mintDuration,
+        _ => return Ignore("No mint duration".to_string()),
+    };
+
+    match duration {
+        0 => Reject("Open ended mints are not allowed".to_string()),
+        _ if duration > max => Reject(format!("Mint duration {} is over {}", duration, max)),
+        _ => Accept,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::address;
+
+    use super::*;
+    use crate::premints::zora_premint::erc20v1::ERC20V1;
+    use crate::premints::zora_premint::v2::V2;
+    use crate::storage::PremintStorage;
+
+    const EXAMPLE: &str = r#"
+        uri_schemes = ["ipfs://", "ar://"]
+        max_price_per_token = "1000000000000000"
+        max_mint_duration = 604800
+
+        [rules]
+        "Metadata::existing_token_uri" = false
+
+        [collections]
+        deny = ["0x0000000000000000000000000000000000000bad"]
+
+        [signers]
+        allow = ["0x00000000000000000000000000000000000000a1"]
+    "#;
+
+    fn v2(uri: &str, price: u128, duration: u64) -> PremintTypes {
+        let mut premint = V2::default();
+        premint.premint.tokenConfig.tokenURI = uri.to_string();
+        premint.premint.tokenConfig.pricePerToken = price;
+        premint.premint.tokenConfig.mintDuration = duration;
+        premint.collection.contractAdmin = address!("00000000000000000000000000000000000000a1");
+        PremintTypes::ZoraV2(premint)
+    }
+
+    #[test]
+    fn test_parse_rules_config() {
+        let config = RulesConfig::from_toml(EXAMPLE).unwrap();
+        assert!(!config.is_enabled("Metadata::existing_token_uri"));
+        assert!(config.is_enabled("Metadata::token_uri_length"));
+        assert_eq!(
+            config.max_price_per_token,
+            Some(U256::from(1_000_000_000_000_000u64))
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(RulesConfig::from_json(&json).unwrap(), config);
+
+        assert!(RulesConfig::from_toml("unknown_setting = 1").is_err());
+    }
+
+    #[test]
+    fn test_declarative_checks() {
+        let config = RulesConfig::from_toml(EXAMPLE).unwrap();
+        assert_eq!(config.declarative_rules::<PremintStorage>().len(), 5);
+
+        let ok = v2("ipfs://abc", 1000, 3600);
+        assert_eq!(uri_scheme_allowed(&config, &ok), Accept);
+        assert_eq!(price_within_cap(&config, &ok), Accept);
+        assert_eq!(mint_duration_within_cap(&config, &ok), Accept);
+        assert_eq!(collection_allowed(&config, &ok), Accept);
+
+        let bad = v2("https://example.com", 10u128.pow(18), 0);
+        assert!(matches!(uri_scheme_allowed(&config, &bad), Reject(_)));
+        assert!(matches!(price_within_cap(&config, &bad), Reject(_)));
+        assert!(matches!(mint_duration_within_cap(&config, &bad), Reject(_)));
+
+        // the default premint's admin isn't on the signer allow list
+        let premint = PremintTypes::ZoraV2(V2::default());
+        assert!(matches!(signer_allowed(&config, &premint), Reject(_)));
+
+        let erc20 = PremintTypes::ZoraERC20V1(ERC20V1::default());
+        assert!(matches!(price_within_cap(&config, &erc20), Ignore(_)));
+    }
+}
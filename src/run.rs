@@ -1,16 +1,17 @@
 use alloy::hex;
+use eyre::WrapErr;
 use libp2p::identity::Keypair;
 use std::time::Duration;
 use tracing::{info_span, Instrument};
 
-use crate::chain::{get_contract_boot_nodes, MintChecker, MintCheckerResult};
+use crate::chain::{claim_routes, get_contract_boot_nodes, MintChecker, MintCheckerResult};
 use crate::chain_list::CHAINS;
 use crate::config::{BootNodes, ChainInclusionMode, Config};
 use crate::controller::{Controller, ControllerCommands, ControllerInterface};
 use crate::p2p::SwarmController;
 use crate::rules::RulesEngine;
 use crate::storage::PremintStorage;
-use crate::types::Premint;
+use crate::webhooks::WebhookDispatcher;
 
 /// Starts the libp2p swarm, the controller, and the checkers if applicable, then wires them all up.
 /// Returns an interface for interacting with the controller.
@@ -27,6 +28,7 @@ pub async fn start_p2p_services(
     let (ext_cmd_send, ext_cmd_recv) = tokio::sync::mpsc::channel(1024);
 
     let store = PremintStorage::new(&config).await;
+    let db = store.db();
 
     let mut swarm_controller = SwarmController::new(id_keys, &config, swrm_recv, event_send);
     let mut controller = Controller::new(
@@ -34,10 +36,12 @@ pub async fn start_p2p_services(
         swrm_cmd_send,
         event_recv,
         ext_cmd_recv,
+        ext_cmd_send.clone(),
         store,
         rules,
     );
     let controller_interface = ControllerInterface::new(ext_cmd_send);
+    let webhooks = WebhookDispatcher::new(&config, db, controller.subscribe_events());
 
     let node_info = swarm_controller.node_info();
     tracing::info!(
@@ -59,6 +63,8 @@ pub async fn start_p2p_services(
         .expect("Swarm controller failed");
     });
 
+    tokio::spawn(webhooks.run());
+
     let node_id = config.node_id;
     tokio::spawn(async move {
         let future = controller.run_loop();
@@ -137,16 +143,36 @@ async fn connect_to_boot_nodes(ctl: &ControllerInterface, boot_nodes: Vec<String
     }
 }
 
-// Used to start processes to watch for new mint events onchain
-pub async fn start_watch_chain<T: Premint>(config: &Config, controller: ControllerInterface) {
+// Used to start processes to watch for new mint events onchain, one per chain for all configured premint types
+pub async fn start_watch_chain(
+    config: &Config,
+    controller: ControllerInterface,
+) -> eyre::Result<()> {
     if config.chain_inclusion_mode == ChainInclusionMode::Check {
+        let kinds = config.premint_names();
         for chain_id in config.supported_chains() {
-            let rpc_url = CHAINS.get_rpc_url(chain_id).expect(format!("Failed to get RPC URL for configured chain_id {chain_id}. Set environment variable CHAIN_{chain_id}_RPC_WSS").as_str());
+            let routes = claim_routes(&kinds, chain_id);
+            if routes.is_empty() {
+                tracing::warn!(
+                    chain_id = chain_id,
+                    "No configured premint types can be minted on chain, skipping checker"
+                );
+                continue;
+            }
 
-            let checker = MintChecker::new(chain_id, rpc_url, controller.clone());
+            CHAINS.get_rpc_urls(chain_id).wrap_err_with(|| {
+                format!("Failed to get RPC URL for configured chain_id {chain_id}. Set environment variable CHAIN_{chain_id}_RPC_WSS")
+            })?;
+
+            let checker = MintChecker::new(
+                chain_id,
+                controller.clone(),
+                routes,
+                config.confirmations_for(chain_id)?,
+            );
             tokio::spawn(async move {
                 loop {
-                    match checker.poll_for_new_mints::<T>().await {
+                    match checker.poll_for_new_mints().await {
                         Ok(MintCheckerResult::NoFilter) => {
                             tracing::warn!(
                                 chain_id = chain_id,
@@ -167,4 +193,5 @@ pub async fn start_watch_chain<T: Premint>(config: &Config, controller: Controll
             tracing::info!(chain_id = chain_id, "Started watching for premints onchain")
         }
     }
+    Ok(())
 }
@@ -1,12 +1,18 @@
 use std::cell::RefCell;
 use std::cmp::{min, Ordering};
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::sync::{Arc, RwLock};
 
+use alloy::primitives::U256;
 use serde::ser::SerializeStruct;
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use xxhash_rust::xxh3::Xxh3;
 
-enum Visit {
+use crate::storage::{QueryOptions, Reader};
+use crate::types::{PremintMetadata, PremintName, PremintTypes};
+
+pub enum Visit {
     /// Continue visiting
     Continue,
 
@@ -17,7 +23,7 @@ enum Visit {
     Stop,
 }
 
-struct TreeSet<T>
+pub struct TreeSet<T>
 where
     T: AsRef<[u8]> + Default + Clone + Eq,
 {
@@ -29,7 +35,7 @@ fn prefix_match<T>(prefix: &[T], path: &Vec<T>, node: &Node<T>) -> bool
 where
     T: AsRef<[u8]> + Sized + Clone + Eq + PartialEq + Default + Serialize + Ord,
 {
-    if prefix.is_empty() || path.is_empty() {
+    if prefix.is_empty() {
         true
     } else {
         let mut full_path = path.clone();
@@ -70,6 +76,11 @@ where
         self.root.insert(path, value, Some(hash), false);
     }
 
+    /// Removes a leaf and prunes any branches left empty, rehashing along the path
+    pub fn remove(&mut self, path: &[T], value: &T) -> bool {
+        self.root.remove(path, value)
+    }
+
     pub fn root(&self) -> &Node<T> {
         &self.root
     }
@@ -98,6 +109,27 @@ where
         leafs.into_inner()
     }
 
+    /// Like `leafs`, but returns the full path to each leaf, including the leaf itself
+    pub fn leaf_paths(&self, prefix: &[T]) -> Vec<Vec<T>> {
+        let paths = RefCell::new(Vec::new());
+
+        self.visit(&|path, node| -> Visit {
+            if !prefix_match(prefix, path, node) {
+                return Visit::Skip;
+            }
+
+            if node.children.is_empty() {
+                let mut full_path = path.clone();
+                full_path.push(node.value.clone());
+                paths.borrow_mut().push(full_path);
+            }
+
+            Visit::Continue
+        });
+
+        paths.into_inner()
+    }
+
     pub fn extract_root(&self, depth: usize) -> TreeSet<T> {
         self.extract(&[], depth)
     }
@@ -120,7 +152,7 @@ where
         sparse.into_inner()
     }
 
-    fn diff(&self, other: &TreeSet<T>) -> Vec<Diff<T>>
+    pub fn diff(&self, other: &TreeSet<T>) -> Vec<Diff<T>>
     where
         T: AsRef<[u8]> + Sized + Clone + Eq + PartialEq + Default + Ord + Serialize,
     {
@@ -133,8 +165,8 @@ where
     }
 }
 
-#[derive(Clone)]
-struct Node<T>
+#[derive(Clone, Debug)]
+pub struct Node<T>
 where
     T: AsRef<[u8]> + Default + Clone + Eq,
 {
@@ -178,6 +210,45 @@ where
     }
 }
 
+impl<'de, T> Deserialize<'de> for Node<T>
+where
+    T: AsRef<[u8]> + Default + Clone + Eq + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct RemoteNode<T> {
+            value: T,
+            hash: u64,
+            children: Vec<RemoteNode<T>>,
+        }
+
+        // hashes received from a peer are taken as-is, so they're treated like manual hashes and
+        // won't be recalculated if the tree is rehashed
+        fn into_node<T>(remote: RemoteNode<T>, level: u64) -> Node<T>
+        where
+            T: AsRef<[u8]> + Default + Clone + Eq,
+        {
+            Node {
+                value: remote.value,
+                level,
+                hash: remote.hash,
+                manual_hash: Some(remote.hash),
+                children: remote
+                    .children
+                    .into_iter()
+                    .map(|child| into_node(child, level + 1))
+                    .collect(),
+                dirty: false,
+            }
+        }
+
+        RemoteNode::deserialize(deserializer).map(|remote| into_node(remote, 0))
+    }
+}
+
 #[derive(Default)]
 struct U64Buffer([u8; 4]);
 
@@ -322,6 +393,41 @@ where
         }
     }
 
+    pub fn remove(&mut self, path: &[T], value: &T) -> bool {
+        let removed = match path {
+            [] => match self
+                .children
+                .binary_search_by(|child| child.value.cmp(value))
+            {
+                Ok(position) => {
+                    self.children.remove(position);
+                    true
+                }
+                Err(_) => false,
+            },
+            [head, tail @ ..] => match self
+                .children
+                .binary_search_by(|child| child.value.cmp(head))
+            {
+                Ok(position) => {
+                    let removed = self.children[position].remove(tail, value);
+                    if removed && self.children[position].children.is_empty() {
+                        self.children.remove(position);
+                    }
+                    removed
+                }
+                Err(_) => false,
+            },
+        };
+
+        if removed {
+            self.dirty = true;
+            self.update_hash();
+        }
+
+        removed
+    }
+
     fn diff(&self, path: &mut Vec<T>, other: &Node<T>) -> Vec<Diff<T>>
     where
         T: AsRef<[u8]> + Sized + Clone + Eq + PartialEq + Default + Ord + Serialize,
@@ -398,6 +504,15 @@ where
     }
 }
 
+impl<T> From<Node<T>> for TreeSet<T>
+where
+    T: AsRef<[u8]> + Default + Clone + Eq,
+{
+    fn from(root: Node<T>) -> Self {
+        Self { root }
+    }
+}
+
 impl<T> Display for TreeSet<T>
 where
     T: AsRef<[u8]> + Sized + Clone + Eq + PartialEq + Default + Display,
@@ -423,19 +538,414 @@ where
 }
 
 #[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
-enum Diff<T> {
+pub enum Diff<T> {
     MissingSelf(Vec<T>),
     MissingOther(Vec<T>),
     HashMismatch(Vec<T>),
 }
 
+/// Number of elements in the path to a premint leaf, including the leaf itself:
+/// kind / chain_id / collection[2..4] / collection[4..6] / collection / token bucket / id
+pub const PREMINT_LEAF_DEPTH: usize = 7;
+
+/// How many levels of the tree are exchanged per summary request
+pub const SUMMARY_DEPTH: usize = 3;
+const MAX_SUMMARY_DEPTH: usize = 4;
+
+/// Maximum number of premints that can be requested in a single fetch
+pub const MAX_FETCH_PATHS: usize = 500;
+
+/// Page size for the query based sync protocol, larger limits are capped to this
+pub const SYNC_PAGE_SIZE: u64 = 500;
+/// Pages followed per query sync, anything past this is left for the next sync
+pub const MAX_SYNC_PAGES: usize = 20;
+
+/// Path in the premint tree under which a premint's id is stored as a leaf
+pub fn premint_path(metadata: &PremintMetadata) -> Vec<String> {
+    let mut path = collection_prefix(
+        &metadata.kind.0,
+        metadata.chain_id,
+        &metadata.collection_address.to_string(),
+    );
+    path.push(format!(
+        "{:0>2}",
+        metadata.token_id.reduce_mod(U256::from(100)).to::<u64>()
+    ));
+    path
+}
+
+/// Path prefix that contains all premints of a collection
+pub fn collection_prefix(kind: &str, chain_id: u64, collection_address: &str) -> Vec<String> {
+    let collection_address = collection_address.to_lowercase();
+
+    vec![
+        kind.to_string(),
+        chain_id.to_string(),
+        collection_address[2..4].to_string(),
+        collection_address[4..6].to_string(),
+        collection_address,
+    ]
+}
+
+/// Prefixes of the premint tree covered by a query, one per premint kind
+pub fn query_prefixes(query: &QueryOptions, names: &[PremintName]) -> Vec<Vec<String>> {
+    let kinds = match &query.kind {
+        Some(kind) => vec![kind.clone()],
+        None => names.iter().map(|name| name.0.clone()).collect(),
+    };
+
+    kinds
+        .into_iter()
+        .map(|kind| match (query.chain_id, query.collection_address) {
+            (Some(chain_id), Some(collection_address)) => {
+                collection_prefix(&kind, chain_id, &collection_address.to_string())
+            }
+            (Some(chain_id), None) => vec![kind, chain_id.to_string()],
+            _ => vec![kind],
+        })
+        .collect()
+}
+
+/// Leafs are hashed by id and version so that a new version of a premint changes the tree
+pub fn premint_hash(metadata: &PremintMetadata) -> u64 {
+    let mut hasher = Xxh3::new();
+    hasher.update(metadata.id.as_bytes());
+    hasher.update(&metadata.version.to_be_bytes());
+    hasher.digest()
+}
+
+/// Builds a hashed tree of the given premints
+pub fn premint_tree(premints: &[PremintTypes]) -> TreeSet<String> {
+    let mut tree = TreeSet::new();
+
+    for premint in premints {
+        let metadata = premint.metadata();
+        let hash = premint_hash(&metadata);
+        tree.insert_with_hash(&premint_path(&metadata), metadata.id, hash);
+    }
+
+    tree.rehash();
+    tree
+}
+
+/// Root hash of the premints of one kind on one chain
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeRoot {
+    pub kind: String,
+    pub chain_id: String,
+    pub hash: String,
+}
+
+/// Premint tree mirroring the active premints in storage, with the version of every leaf since
+/// the leaf hashes don't tell which of two versions is newer.
+/// Clones share the same tree, so updates from the storage are visible to all of them.
+#[derive(Clone)]
+pub struct PremintIndex(
+    Arc<RwLock<TreeSet<String>>>,
+    Arc<RwLock<HashMap<(String, String), u64>>>,
+);
+
+impl PremintIndex {
+    pub fn new(premints: &[PremintTypes]) -> Self {
+        let versions = premints
+            .iter()
+            .map(|premint| {
+                let metadata = premint.metadata();
+                ((metadata.kind.0, metadata.id), metadata.version)
+            })
+            .collect();
+
+        Self(
+            Arc::new(RwLock::new(premint_tree(premints))),
+            Arc::new(RwLock::new(versions)),
+        )
+    }
+
+    pub fn insert(&self, metadata: &PremintMetadata) {
+        let mut tree = self.0.write().expect("premint index lock poisoned");
+        tree.insert_with_hash_update(
+            &premint_path(metadata),
+            metadata.id.clone(),
+            premint_hash(metadata),
+        );
+        drop(tree);
+
+        let mut versions = self.1.write().expect("premint index lock poisoned");
+        versions.insert(
+            (metadata.kind.0.clone(), metadata.id.clone()),
+            metadata.version,
+        );
+    }
+
+    pub fn remove(&self, metadata: &PremintMetadata) {
+        let mut tree = self.0.write().expect("premint index lock poisoned");
+        tree.remove(&premint_path(metadata), &metadata.id);
+        drop(tree);
+
+        let mut versions = self.1.write().expect("premint index lock poisoned");
+        versions.remove(&(metadata.kind.0.clone(), metadata.id.clone()));
+    }
+
+    /// Version of the premint at a leaf path, None if we don't have it
+    pub fn version(&self, leaf_path: &[String]) -> Option<u64> {
+        if leaf_path.len() != PREMINT_LEAF_DEPTH {
+            return None;
+        }
+
+        let versions = self.1.read().expect("premint index lock poisoned");
+        let key = (
+            leaf_path[0].clone(),
+            leaf_path[PREMINT_LEAF_DEPTH - 1].clone(),
+        );
+        versions.get(&key).copied()
+    }
+
+    /// Runs `f` with read access to the tree. Don't hold on to anything across awaits in here.
+    pub fn read<R>(&self, f: impl FnOnce(&TreeSet<String>) -> R) -> R {
+        let tree = self.0.read().expect("premint index lock poisoned");
+        f(&tree)
+    }
+
+    pub fn roots(&self) -> Vec<TreeRoot> {
+        self.read(|tree| {
+            tree.root()
+                .children()
+                .iter()
+                .flat_map(|kind| {
+                    kind.children().iter().map(|chain| TreeRoot {
+                        kind: kind.value.clone(),
+                        chain_id: chain.value.clone(),
+                        hash: format!("{:016x}", chain.hash),
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+/// Requests for the `/mintpool-tree-sync/1` protocol.
+///
+/// A sync starts by asking a peer for a summary of the subtree under a prefix (i.e. kind or
+/// kind/chain). Subtrees where the hashes differ from our own are requested again with a longer
+/// prefix until the mismatches are at leaf level, then only the missing premints get fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TreeSyncRequest {
+    Summary { prefix: Vec<String>, depth: usize },
+    Fetch { paths: Vec<Vec<String>> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum TreeSyncResponse {
+    Summary {
+        prefix: Vec<String>,
+        depth: usize,
+        tree: Node<String>,
+        // versions of the premint leafs in the tree by id, so a mismatching leaf is only fetched
+        // if the peer's version is newer
+        #[serde(default)]
+        versions: HashMap<String, u64>,
+    },
+    Premints(Vec<PremintTypes>),
+    Error(String),
+}
+
+/// Answers a tree sync request from a peer using our own premint tree
+pub async fn respond_to_tree_sync<R: Reader>(
+    index: &PremintIndex,
+    request: TreeSyncRequest,
+    store: &R,
+) -> eyre::Result<TreeSyncResponse> {
+    match request {
+        TreeSyncRequest::Summary { prefix, depth } => {
+            let depth = min(depth, MAX_SUMMARY_DEPTH);
+            let (tree, leaf_paths) = index.read(|tree| {
+                let summary = tree.extract(&prefix, depth);
+                (summary.root().clone(), summary.leaf_paths(&prefix))
+            });
+            let versions = leaf_paths
+                .iter()
+                .filter_map(|path| {
+                    let version = index.version(path)?;
+                    Some((path[PREMINT_LEAF_DEPTH - 1].clone(), version))
+                })
+                .collect();
+
+            Ok(TreeSyncResponse::Summary {
+                prefix,
+                depth,
+                tree,
+                versions,
+            })
+        }
+        TreeSyncRequest::Fetch { paths } => {
+            if paths.len() > MAX_FETCH_PATHS {
+                return Err(eyre::eyre!(
+                    "Too many paths requested: {} > {}",
+                    paths.len(),
+                    MAX_FETCH_PATHS
+                ));
+            }
+
+            let mut premints = Vec::with_capacity(paths.len());
+            for path in paths {
+                if path.len() != PREMINT_LEAF_DEPTH {
+                    continue;
+                }
+
+                let kind = PremintName(path[0].clone());
+                let id = &path[PREMINT_LEAF_DEPTH - 1];
+                match store.get_for_id_and_kind(id, kind).await {
+                    Ok(premint) => premints.push(premint),
+                    Err(err) => tracing::debug!("Requested premint not found: {:?}", err),
+                }
+            }
+
+            Ok(TreeSyncResponse::Premints(premints))
+        }
+    }
+}
+
+/// Checks that a tree sync response answers the request it was sent for.
+/// Premints we didn't ask for are dropped, the returned count says how many.
+pub fn verify_tree_sync_response(
+    request: &TreeSyncRequest,
+    response: TreeSyncResponse,
+) -> eyre::Result<(TreeSyncResponse, usize)> {
+    match (request, response) {
+        (
+            TreeSyncRequest::Summary {
+                prefix: requested_prefix,
+                depth: requested_depth,
+            },
+            TreeSyncResponse::Summary {
+                prefix,
+                depth,
+                tree,
+                versions,
+            },
+        ) => {
+            if prefix != *requested_prefix || depth > *requested_depth {
+                return Err(eyre::eyre!(
+                    "Summary for {:?} (depth {}) does not match request for {:?} (depth {})",
+                    prefix,
+                    depth,
+                    requested_prefix,
+                    requested_depth
+                ));
+            }
+
+            Ok((
+                TreeSyncResponse::Summary {
+                    prefix,
+                    depth,
+                    tree,
+                    versions,
+                },
+                0,
+            ))
+        }
+        (TreeSyncRequest::Fetch { paths }, TreeSyncResponse::Premints(premints)) => {
+            let total = premints.len();
+            let premints: Vec<_> = premints
+                .into_iter()
+                .filter(|p| {
+                    let metadata = p.metadata();
+                    let mut path = premint_path(&metadata);
+                    path.push(metadata.id);
+                    paths.contains(&path)
+                })
+                .collect();
+
+            let dropped = total - premints.len();
+            Ok((TreeSyncResponse::Premints(premints), dropped))
+        }
+        (_, TreeSyncResponse::Error(err)) => Ok((TreeSyncResponse::Error(err), 0)),
+        (request, _) => Err(eyre::eyre!("Wrong response type for request {:?}", request)),
+    }
+}
+
+/// Outcome of syncing with a single peer, accumulated over all sync rounds
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PeerSyncStats {
+    pub responses: u64,
+    // premints that passed the rules engine and were stored
+    pub accepted: u64,
+    // premints that were rejected by the rules engine
+    pub rejected: u64,
+    // premints or responses that didn't match what we asked for
+    pub invalid: u64,
+    pub timeouts: u64,
+}
+
+/// Compares a summary received from a peer with our own tree.
+/// Returns the follow-up requests needed to get the premints we are missing or have an older
+/// version of. `local_version` gives the version we have of the premint at a leaf path.
+pub fn follow_up_requests(
+    local: &TreeSet<String>,
+    prefix: &[String],
+    depth: usize,
+    remote: Node<String>,
+    remote_versions: &HashMap<String, u64>,
+    local_version: impl Fn(&[String]) -> Option<u64>,
+) -> Vec<TreeSyncRequest> {
+    let remote = TreeSet::from(remote);
+    let local = local.extract(prefix, depth);
+
+    let mut fetch = Vec::new();
+    let mut deeper = Vec::new();
+
+    for diff in local.diff(&remote) {
+        let paths = match diff {
+            // we're missing the whole subtree, so follow every branch the peer sent us
+            Diff::MissingSelf(path) => remote.leaf_paths(&path),
+            Diff::HashMismatch(path) => vec![path],
+            // the peer is missing these, they can fetch them from us
+            Diff::MissingOther(_) => continue,
+        };
+
+        for path in paths {
+            // ignore anything outside of the subtree we asked for
+            if path.len() <= prefix.len() || path[..prefix.len()] != *prefix {
+                continue;
+            }
+
+            if path.len() >= PREMINT_LEAF_DEPTH {
+                let path = &path[..PREMINT_LEAF_DEPTH];
+                // peers without versions in their summaries get everything fetched
+                let remote_version = remote_versions.get(&path[PREMINT_LEAF_DEPTH - 1]);
+                if let (Some(remote), Some(local)) = (remote_version, local_version(path)) {
+                    if local >= *remote {
+                        continue;
+                    }
+                }
+                fetch.push(path.to_vec());
+            } else {
+                deeper.push(TreeSyncRequest::Summary {
+                    prefix: path,
+                    depth: SUMMARY_DEPTH,
+                });
+            }
+        }
+    }
+
+    deeper.extend(
+        fetch
+            .chunks(MAX_FETCH_PATHS)
+            .map(|paths| TreeSyncRequest::Fetch {
+                paths: paths.to_vec(),
+            }),
+    );
+
+    deeper
+}
+
 #[cfg(test)]
 mod tests {
     use alloy::primitives::{Address, U256};
     use rand::thread_rng;
     use rand::Rng;
 
-    use crate::types::{PremintMetadata, PremintName};
+    use crate::types::{Premint, PremintMetadata, PremintName, SimplePremint};
 
     use super::*;
 
@@ -491,6 +1001,166 @@ mod tests {
         assert_eq!(leafs, vec!["1", "2", "3", "4", "5"]);
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_premint_tree_follow_up() {
+        let sender = Address::new([1; 20]);
+        let premint = |version, token_id| {
+            PremintTypes::Simple(SimplePremint::new(
+                version,
+                7777777,
+                sender,
+                token_id,
+                format!("ipfs://{}", token_id),
+            ))
+        };
+
+        let remote_premints = [premint(1, 1), premint(2, 2), premint(1, 3), premint(1, 4)];
+        let local_premints = [premint(1, 1), premint(1, 2), premint(2, 4)];
+        let remote = premint_tree(&remote_premints);
+        let remote_versions: HashMap<String, u64> = remote_premints
+            .iter()
+            .map(|p| (p.metadata().id, p.metadata().version))
+            .collect();
+        let local = premint_tree(&local_premints);
+        let local_index = PremintIndex::new(&local_premints);
+
+        // walk the tree like a peer would, answering summaries with the remote tree
+        let prefix = vec!["simple".to_string()];
+        let mut requests = vec![TreeSyncRequest::Summary {
+            prefix,
+            depth: SUMMARY_DEPTH,
+        }];
+        let mut fetched = Vec::new();
+
+        while let Some(request) = requests.pop() {
+            match request {
+                TreeSyncRequest::Summary { prefix, depth } => {
+                    let summary = remote.extract(&prefix, depth);
+
+                    // make sure the tree survives the trip over the wire
+                    let bytes = serde_cbor::to_vec(summary.root()).unwrap();
+                    let received: Node<String> = serde_cbor::from_slice(&bytes).unwrap();
+
+                    requests.extend(follow_up_requests(
+                        &local,
+                        &prefix,
+                        depth,
+                        received,
+                        &remote_versions,
+                        |path| local_index.version(path),
+                    ));
+                }
+                TreeSyncRequest::Fetch { paths } => fetched.extend(paths),
+            }
+        }
+
+        let mut fetched_ids: Vec<String> = fetched
+            .iter()
+            .map(|path| path[PREMINT_LEAF_DEPTH - 1].clone())
+            .collect();
+        fetched_ids.sort();
+
+        // token 4 differs as well, but we have the newer version of it
+        assert_eq!(
+            fetched_ids,
+            vec![premint(2, 2).metadata().id, premint(1, 3).metadata().id]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_premint_index_incremental() {
+        let sender = Address::new([1; 20]);
+        let premint = |version, token_id| {
+            SimplePremint::new(
+                version,
+                7777777,
+                sender,
+                token_id,
+                format!("ipfs://{}", token_id),
+            )
+            .metadata()
+        };
+
+        let index = PremintIndex::new(&[]);
+        index.insert(&premint(1, 1));
+        index.insert(&premint(1, 2));
+        index.insert(&premint(2, 2));
+        index.insert(&premint(1, 3));
+        index.remove(&premint(1, 3));
+
+        let rebuilt = premint_tree(&[
+            PremintTypes::Simple(SimplePremint::new(
+                1,
+                7777777,
+                sender,
+                1,
+                "ipfs://1".to_string(),
+            )),
+            PremintTypes::Simple(SimplePremint::new(
+                2,
+                7777777,
+                sender,
+                2,
+                "ipfs://2".to_string(),
+            )),
+        ]);
+
+        index.read(|tree| assert!(tree.diff(&rebuilt).is_empty()));
+
+        let leaf_path = |metadata: &PremintMetadata| {
+            let mut path = premint_path(metadata);
+            path.push(metadata.id.clone());
+            path
+        };
+        assert_eq!(index.version(&leaf_path(&premint(1, 2))), Some(2));
+        assert_eq!(index.version(&leaf_path(&premint(1, 3))), None);
+
+        let roots = index.roots();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].kind, "simple");
+        assert_eq!(roots[0].chain_id, "7777777");
+
+        // removing everything leaves an empty tree
+        index.remove(&premint(1, 1));
+        index.remove(&premint(2, 2));
+        assert!(index.roots().is_empty());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_verify_tree_sync_response() {
+        let sender = Address::default();
+        let requested =
+            PremintTypes::Simple(SimplePremint::new(1, 7777777, sender, 1, "a".to_string()));
+        let unrequested =
+            PremintTypes::Simple(SimplePremint::new(1, 7777777, sender, 2, "b".to_string()));
+
+        let metadata = requested.metadata();
+        let mut path = premint_path(&metadata);
+        path.push(metadata.id);
+        let request = TreeSyncRequest::Fetch { paths: vec![path] };
+
+        let response = TreeSyncResponse::Premints(vec![requested.clone(), unrequested]);
+        let (response, dropped) = verify_tree_sync_response(&request, response).unwrap();
+        assert_eq!(dropped, 1);
+        match response {
+            TreeSyncResponse::Premints(premints) => assert_eq!(premints, vec![requested]),
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        // summaries have to be for the prefix we asked for
+        let request = TreeSyncRequest::Summary {
+            prefix: vec!["simple".to_string()],
+            depth: SUMMARY_DEPTH,
+        };
+        let response = TreeSyncResponse::Summary {
+            prefix: vec!["zora_premint_v2".to_string()],
+            depth: SUMMARY_DEPTH,
+            tree: Node::default(),
+            versions: HashMap::new(),
+        };
+        assert!(verify_tree_sync_response(&request, response).is_err());
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_large_tree() {
         let mut set1 = TreeSet::new();
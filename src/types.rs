@@ -1,6 +1,6 @@
 use crate::premints::zora_premint::{erc20v1, v2};
 use alloy::primitives::{Address, B256, U256};
-use alloy::rpc::types::eth::{Filter, Log, TransactionReceipt};
+use alloy::rpc::types::eth::{Log, TransactionReceipt};
 use async_trait::async_trait;
 use libp2p::gossipsub::TopicHash;
 use libp2p::{gossipsub, Multiaddr, PeerId};
@@ -44,10 +44,18 @@ pub struct PremintMetadata {
     pub uri: String,
 }
 
+/// Contract event emitted when a premint is brought onchain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClaimEvent {
+    pub address: Address,
+    pub signature: B256,
+}
+
 #[async_trait]
 pub trait Premint: Serialize + DeserializeOwned + Debug + Clone {
     fn metadata(&self) -> PremintMetadata;
-    fn check_filter(chain_id: u64) -> Option<Filter>;
+    // None when the premint type can't be brought onchain on the chain
+    fn claim_event(chain_id: u64) -> Option<ClaimEvent>;
     fn map_claim(chain_id: u64, log: Log) -> eyre::Result<InclusionClaim>;
     async fn verify_claim(
         &self,
@@ -145,8 +153,8 @@ impl Premint for SimplePremint {
         }
     }
 
-    fn check_filter(_chain_id: u64) -> Option<Filter> {
-        todo!()
+    fn claim_event(_chain_id: u64) -> Option<ClaimEvent> {
+        None
     }
 
     fn map_claim(_chain_id: u64, _log: Log) -> eyre::Result<InclusionClaim> {
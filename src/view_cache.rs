@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use alloy::primitives::{Address, Bytes, B256};
+use mini_moka::sync::Cache;
+use once_cell::sync::Lazy;
+
+/// How long a cached view call result is used before it's fetched again
+const VIEW_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+const VIEW_CACHE_CAPACITY: u64 = 10_000;
+
+/// Block tag the cached calls are made against, view calls always read the latest state
+pub const LATEST: &str = "latest";
+
+pub static VIEW_CACHE: Lazy<ViewCache> = Lazy::new(|| ViewCache::new(VIEW_CACHE_TTL));
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ViewCallKey {
+    pub chain_id: u64,
+    pub contract: Address,
+    pub calldata: Bytes,
+    pub block_tag: &'static str,
+}
+
+impl ViewCallKey {
+    // true if the call targets one of the words, or passes one as an argument
+    fn mentions(&self, words: &[B256]) -> bool {
+        let contract = self.contract.into_word();
+        if words.contains(&contract) {
+            return true;
+        }
+
+        // static arguments are abi encoded as 32 byte words after the 4 byte selector
+        self.calldata
+            .get(4..)
+            .map(|args| {
+                args.chunks_exact(32)
+                    .any(|arg| words.iter().any(|word| word.as_slice() == arg))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// Results of contract view calls that rarely change, shared across chains
+pub struct ViewCache(Cache<ViewCallKey, Bytes>);
+
+impl ViewCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self(
+            Cache::builder()
+                .max_capacity(VIEW_CACHE_CAPACITY)
+                .time_to_live(ttl)
+                .build(),
+        )
+    }
+
+    pub fn get(&self, key: &ViewCallKey) -> Option<Bytes> {
+        let cached = self.0.get(key);
+        match cached {
+            Some(_) => tracing::info!(counter.view_cache_hits = 1, chain_id = key.chain_id),
+            None => tracing::info!(counter.view_cache_misses = 1, chain_id = key.chain_id),
+        }
+        cached
+    }
+
+    pub fn insert(&self, key: ViewCallKey, response: Bytes) {
+        self.0.insert(key, response);
+    }
+
+    /// Drops the cached calls on a chain that target or take as argument any of the given words,
+    /// e.g. the indexed topics of an event about a collection. Returns how many were dropped.
+    pub fn invalidate(&self, chain_id: u64, words: &[B256]) -> usize {
+        let stale: Vec<ViewCallKey> = self
+            .0
+            .iter()
+            .filter(|entry| entry.key().chain_id == chain_id && entry.key().mentions(words))
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for key in &stale {
+            self.0.invalidate(key);
+        }
+
+        if !stale.is_empty() {
+            tracing::info!(
+                counter.view_cache_invalidations = stale.len() as u64,
+                chain_id = chain_id
+            );
+        }
+        stale.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::address;
+
+    use super::*;
+
+    fn key(chain_id: u64, collection: Address) -> ViewCallKey {
+        let mut calldata = vec![0xde, 0xad, 0xbe, 0xef];
+        calldata.extend_from_slice(collection.into_word().as_slice());
+        ViewCallKey {
+            chain_id,
+            contract: address!("7777773606e7e46C8Ba8B98C08f5cD218e31d340"),
+            calldata: calldata.into(),
+            block_tag: LATEST,
+        }
+    }
+
+    #[test]
+    fn test_view_cache_invalidate() {
+        let cache = ViewCache::new(Duration::from_secs(60));
+        let collection = address!("65aae9d752ecac4965015664d0a6d0951e28d757");
+        let other = address!("0000000000000000000000000000000000000001");
+
+        cache.insert(key(7777777, collection), Bytes::from(vec![1]));
+        cache.insert(key(7777777, other), Bytes::from(vec![2]));
+        cache.insert(key(8453, collection), Bytes::from(vec![3]));
+        assert_eq!(
+            cache.get(&key(7777777, collection)),
+            Some(Bytes::from(vec![1]))
+        );
+
+        // only calls about the collection on the same chain are dropped
+        assert_eq!(cache.invalidate(7777777, &[collection.into_word()]), 1);
+        assert_eq!(cache.get(&key(7777777, collection)), None);
+        assert!(cache.get(&key(7777777, other)).is_some());
+        assert!(cache.get(&key(8453, collection)).is_some());
+
+        // calls to the contract itself are dropped too
+        let factory = key(8453, collection).contract;
+        assert_eq!(cache.invalidate(8453, &[factory.into_word()]), 1);
+        assert_eq!(cache.get(&key(8453, collection)), None);
+    }
+}
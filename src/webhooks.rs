@@ -0,0 +1,555 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use alloy::primitives::Address;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::any::AnyRow;
+use sqlx::{AnyPool, Row};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::config::Config;
+use crate::events::PremintEvent;
+use crate::storage::QueryOptions;
+
+pub const SIGNATURE_HEADER: &str = "X-Mintpool-Signature";
+pub const EVENT_HEADER: &str = "X-Mintpool-Event";
+pub const TIMESTAMP_HEADER: &str = "X-Mintpool-Timestamp";
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// An endpoint premint events are POSTed to. Events are only sent if they match all filters that are set.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    // key for the HMAC-SHA256 signature of each payload, only shown once when the webhook is created
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub kind: Option<String>,
+    pub chain_id: Option<u64>,
+    pub collection_address: Option<Address>,
+    pub creator_address: Option<Address>,
+    pub deliveries: u64,
+    pub failures: u64,
+    pub dead_letters: u64,
+    pub created_at: String,
+}
+
+impl Webhook {
+    pub fn matches(&self, event: &PremintEvent) -> bool {
+        event.matches(&QueryOptions {
+            kind: self.kind.clone(),
+            chain_id: self.chain_id,
+            collection_address: self.collection_address,
+            creator_address: self.creator_address,
+            ..Default::default()
+        })
+    }
+}
+
+/// Response to creating a webhook, the only time its secret is returned
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+impl From<Webhook> for CreatedWebhook {
+    fn from(webhook: Webhook) -> Self {
+        let secret = webhook.secret.clone();
+        Self { webhook, secret }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewWebhook {
+    pub url: String,
+    // generated if not given
+    pub secret: Option<String>,
+    pub kind: Option<String>,
+    pub chain_id: Option<u64>,
+    pub collection_address: Option<Address>,
+    pub creator_address: Option<Address>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub webhook_id: String,
+    pub event: serde_json::Value,
+    pub error: String,
+    pub attempts: u64,
+    pub failed_at: String,
+}
+
+pub async fn create_webhook(db: &AnyPool, new: NewWebhook) -> eyre::Result<Webhook> {
+    url::Url::parse(&new.url).map_err(|e| eyre::eyre!("Invalid webhook url: {}", e))?;
+
+    let webhook = Webhook {
+        id: random_hex(16),
+        url: new.url,
+        secret: new.secret.unwrap_or_else(|| random_hex(32)),
+        kind: new.kind,
+        chain_id: new.chain_id,
+        collection_address: new.collection_address,
+        creator_address: new.creator_address,
+        deliveries: 0,
+        failures: 0,
+        dead_letters: 0,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    sqlx::query(
+        r#"
+            INSERT INTO webhooks (id, url, secret, kind, chain_id, collection_address, creator_address, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(webhook.id.clone())
+    .bind(webhook.url.clone())
+    .bind(webhook.secret.clone())
+    .bind(webhook.kind.clone())
+    .bind(webhook.chain_id.map(|c| c as i64))
+    .bind(webhook.collection_address.map(|a| a.to_string()))
+    .bind(webhook.creator_address.map(|a| a.to_string()))
+    .bind(webhook.created_at.clone())
+    .execute(db)
+    .await
+    .map_err(|e| eyre::eyre!("Failed to create webhook: {}", e))?;
+
+    Ok(webhook)
+}
+
+pub async fn list_webhooks(db: &AnyPool) -> eyre::Result<Vec<Webhook>> {
+    let rows = sqlx::query(
+        r#"
+            SELECT id, url, secret, kind, chain_id, collection_address, creator_address,
+                   deliveries, failures, dead_letters, created_at
+            FROM webhooks ORDER BY created_at
+        "#,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| eyre::eyre!("Failed to list webhooks: {}", e))?;
+
+    rows.iter().map(webhook_from_row).collect()
+}
+
+/// Removes a webhook and its dead letters, returns false if there was no such webhook
+pub async fn delete_webhook(db: &AnyPool, id: &str) -> eyre::Result<bool> {
+    sqlx::query("DELETE FROM webhook_dead_letters WHERE webhook_id = $1")
+        .bind(id)
+        .execute(db)
+        .await?;
+    let res = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+        .bind(id)
+        .execute(db)
+        .await?;
+
+    Ok(res.rows_affected() > 0)
+}
+
+pub async fn list_dead_letters(db: &AnyPool, webhook_id: &str) -> eyre::Result<Vec<DeadLetter>> {
+    let rows = sqlx::query(
+        r#"
+            SELECT webhook_id, event, error, attempts, failed_at
+            FROM webhook_dead_letters WHERE webhook_id = $1 ORDER BY failed_at
+        "#,
+    )
+    .bind(webhook_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| eyre::eyre!("Failed to list dead letters: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            let event: String = row.try_get("event")?;
+            Ok(DeadLetter {
+                webhook_id: row.try_get("webhook_id")?,
+                event: serde_json::from_str(&event)?,
+                error: row.try_get("error")?,
+                attempts: row.try_get::<i64, _>("attempts")? as u64,
+                failed_at: row.try_get("failed_at")?,
+            })
+        })
+        .collect()
+}
+
+fn webhook_from_row(row: &AnyRow) -> eyre::Result<Webhook> {
+    let address = |column: &str| -> eyre::Result<Option<Address>> {
+        row.try_get::<Option<String>, _>(column)?
+            .map(|a| Address::from_str(&a))
+            .transpose()
+            .map_err(|e| eyre::eyre!("Invalid address in webhook: {}", e))
+    };
+
+    Ok(Webhook {
+        id: row.try_get("id")?,
+        url: row.try_get("url")?,
+        secret: row.try_get("secret")?,
+        kind: row.try_get("kind")?,
+        chain_id: row.try_get::<Option<i64>, _>("chain_id")?.map(|c| c as u64),
+        collection_address: address("collection_address")?,
+        creator_address: address("creator_address")?,
+        deliveries: row.try_get::<i64, _>("deliveries")? as u64,
+        failures: row.try_get::<i64, _>("failures")? as u64,
+        dead_letters: row.try_get::<i64, _>("dead_letters")? as u64,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    const_hex::encode(buf)
+}
+
+/// Hex encoded HMAC-SHA256 of the payload, sent as `sha256=<signature>` so receivers can verify it came from us
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload.as_bytes());
+    const_hex::encode(mac.finalize().into_bytes())
+}
+
+/// Signature of a delivery, covering the unix timestamp sent in `X-Mintpool-Timestamp` as well as the body
+/// so receivers can reject replayed deliveries
+pub fn sign_delivery(secret: &str, timestamp: i64, payload: &str) -> String {
+    sign(secret, &format!("{timestamp}.{payload}"))
+}
+
+/// Posts premint events to every matching webhook.
+/// Each delivery runs in its own task, retrying with exponential backoff until it succeeds
+/// or runs out of attempts, at which point the event is kept as a dead letter.
+pub struct WebhookDispatcher {
+    db: AnyPool,
+    client: reqwest::Client,
+    events: broadcast::Receiver<PremintEvent>,
+    max_attempts: u32,
+}
+
+impl WebhookDispatcher {
+    pub fn new(config: &Config, db: AnyPool, events: broadcast::Receiver<PremintEvent>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.webhook_timeout_secs))
+            .build()
+            .expect("Failed to build webhook http client");
+
+        Self {
+            db,
+            client,
+            events,
+            max_attempts: config.webhook_max_attempts.max(1),
+        }
+    }
+
+    pub async fn run(mut self) {
+        loop {
+            match self.events.recv().await {
+                Ok(event) => {
+                    if let Err(err) = self.dispatch(event).await {
+                        tracing::error!("Error dispatching webhooks: {:?}", err);
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!(missed = missed, "Webhook dispatcher fell behind on events");
+                    tracing::info!(counter.webhook_events_missed = missed);
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    async fn dispatch(&self, event: PremintEvent) -> eyre::Result<()> {
+        let webhooks = list_webhooks(&self.db).await?;
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_string(&event)?;
+        for webhook in webhooks.into_iter().filter(|w| w.matches(&event)) {
+            let delivery = Delivery {
+                db: self.db.clone(),
+                client: self.client.clone(),
+                webhook,
+                event_name: event.name(),
+                payload: payload.clone(),
+                max_attempts: self.max_attempts,
+            };
+            tokio::spawn(delivery.run());
+        }
+        Ok(())
+    }
+}
+
+struct Delivery {
+    db: AnyPool,
+    client: reqwest::Client,
+    webhook: Webhook,
+    event_name: &'static str,
+    payload: String,
+    max_attempts: u32,
+}
+
+impl Delivery {
+    async fn run(self) {
+        let webhook_id = self.webhook.id.as_str();
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let start = Instant::now();
+            let result = self.post().await;
+            tracing::info!(
+                histogram.webhook_delivery_duration = start.elapsed().as_millis() as u64,
+                webhook_id = webhook_id
+            );
+
+            match result {
+                Ok(_) => {
+                    tracing::info!(counter.webhook_deliveries = 1, webhook_id = webhook_id);
+                    self.count("deliveries").await;
+                    return;
+                }
+                Err(err) if attempts < self.max_attempts => {
+                    self.count("failures").await;
+                    tracing::debug!(
+                        webhook_id = webhook_id,
+                        attempts = attempts,
+                        "Webhook delivery failed, retrying: {:?}",
+                        err
+                    );
+                    tracing::info!(counter.webhook_retries = 1, webhook_id = webhook_id);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        webhook_id = webhook_id,
+                        attempts = attempts,
+                        "Webhook delivery failed, giving up: {:?}",
+                        err
+                    );
+                    tracing::info!(counter.webhook_dead_letters = 1, webhook_id = webhook_id);
+                    self.count("failures").await;
+                    if let Err(err) = self.dead_letter(&err.to_string(), attempts).await {
+                        tracing::error!("Error storing webhook dead letter: {:?}", err);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    // signed on every attempt so retries carry a fresh timestamp
+    async fn post(&self) -> eyre::Result<()> {
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = format!(
+            "sha256={}",
+            sign_delivery(&self.webhook.secret, timestamp, &self.payload)
+        );
+
+        self.client
+            .post(&self.webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, self.event_name)
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(SIGNATURE_HEADER, signature)
+            .body(self.payload.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    // bumps one of the per webhook counters, these are shown in the admin api
+    async fn count(&self, column: &'static str) {
+        let sql = format!("UPDATE webhooks SET {column} = {column} + 1 WHERE id = $1");
+        if let Err(err) = sqlx::query(&sql)
+            .bind(self.webhook.id.clone())
+            .execute(&self.db)
+            .await
+        {
+            tracing::error!("Error updating webhook {}: {:?}", column, err);
+        }
+    }
+
+    async fn dead_letter(&self, error: &str, attempts: u32) -> eyre::Result<()> {
+        sqlx::query(
+            r#"
+                INSERT INTO webhook_dead_letters (webhook_id, event, error, attempts, failed_at)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(self.webhook.id.clone())
+        .bind(self.payload.clone())
+        .bind(error.to_string())
+        .bind(attempts as i64)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&self.db)
+        .await?;
+        self.count("dead_letters").await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::Router;
+
+    use super::*;
+    use crate::storage::PremintStorage;
+    use crate::types::{PremintTypes, SimplePremint};
+
+    fn accepted(chain_id: u64) -> PremintEvent {
+        let premint = SimplePremint::new(1, chain_id, Address::default(), 1, String::new());
+        PremintEvent::Accepted {
+            premint: PremintTypes::Simple(premint),
+        }
+    }
+
+    fn new_webhook(url: &str) -> NewWebhook {
+        NewWebhook {
+            url: url.to_string(),
+            secret: Some("secret".to_string()),
+            kind: None,
+            chain_id: None,
+            collection_address: None,
+            creator_address: None,
+        }
+    }
+
+    #[test]
+    fn test_sign() {
+        // well known HMAC-SHA256 test vector
+        assert_eq!(
+            sign("key", "The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_webhook_crud_and_filters() {
+        let store = PremintStorage::new(&Config::test_default()).await;
+        let db = store.db();
+
+        let created = create_webhook(
+            &db,
+            NewWebhook {
+                chain_id: Some(7777777),
+                secret: None,
+                ..new_webhook("http://localhost:1234/hook")
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(created.secret.len(), 64);
+
+        // the secret is only serialized in the response to creating the webhook
+        let json = serde_json::to_value(&created).unwrap();
+        assert!(json.get("secret").is_none());
+        let json = serde_json::to_value(CreatedWebhook::from(created.clone())).unwrap();
+        assert_eq!(json["secret"], created.secret);
+        assert_eq!(json["id"], created.id);
+        assert!(create_webhook(&db, new_webhook("not a url")).await.is_err());
+
+        let listed = list_webhooks(&db).await.unwrap();
+        assert_eq!(listed, vec![created.clone()]);
+
+        assert!(created.matches(&accepted(7777777)));
+        assert!(!created.matches(&accepted(8453)));
+
+        assert!(delete_webhook(&db, &created.id).await.unwrap());
+        assert!(!delete_webhook(&db, &created.id).await.unwrap());
+        assert!(list_webhooks(&db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_and_dead_letters() {
+        let config = Config {
+            webhook_max_attempts: 2,
+            ..Config::test_default()
+        };
+        let store = PremintStorage::new(&config).await;
+        let db = store.db();
+
+        let received: Arc<Mutex<Vec<(HeaderMap, String)>>> = Default::default();
+        let router = Router::new().route(
+            "/hook",
+            post({
+                let received = received.clone();
+                move |headers: HeaderMap, body: String| async move {
+                    received.lock().unwrap().push((headers, body));
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let good = create_webhook(&db, new_webhook(&format!("http://{}/hook", addr)))
+            .await
+            .unwrap();
+        let bad = create_webhook(&db, new_webhook(&format!("http://{}/missing", addr)))
+            .await
+            .unwrap();
+
+        let (sender, receiver) = broadcast::channel(16);
+        tokio::spawn(WebhookDispatcher::new(&config, db.clone(), receiver).run());
+        sender.send(accepted(7777777)).unwrap();
+
+        // deliveries run on their own tasks and the failing hook retries once after a second,
+        // the dead letter count is bumped last so both hooks are done once it's set
+        let deadline = Instant::now() + Duration::from_secs(30);
+        loop {
+            let webhooks = list_webhooks(&db).await.unwrap();
+            let done = |id: &str, check: fn(&Webhook) -> bool| {
+                webhooks.iter().find(|w| w.id == id).is_some_and(check)
+            };
+            if done(&good.id, |w| w.deliveries > 0) && done(&bad.id, |w| w.dead_letters > 0) {
+                break;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "webhook deliveries didn't finish in time"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 1);
+            let (headers, body) = &received[0];
+            assert_eq!(headers[EVENT_HEADER], "accepted");
+            let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+            assert!((chrono::Utc::now().timestamp() - timestamp).abs() < 60);
+            assert_eq!(
+                headers[SIGNATURE_HEADER].to_str().unwrap(),
+                format!("sha256={}", sign_delivery(&good.secret, timestamp, body))
+            );
+        }
+
+        let dead_letters = list_dead_letters(&db, &bad.id).await.unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].attempts, 2);
+        assert_eq!(dead_letters[0].event["type"], "accepted");
+
+        let webhooks = list_webhooks(&db).await.unwrap();
+        let stats = |id: &str| {
+            let w = webhooks.iter().find(|w| w.id == id).unwrap();
+            (w.deliveries, w.failures, w.dead_letters)
+        };
+        assert_eq!(stats(&good.id), (1, 0, 0));
+        assert_eq!(stats(&bad.id), (0, 2, 1));
+    }
+}
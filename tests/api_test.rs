@@ -10,6 +10,7 @@ mod api_test {
     use mintpool::config::Config;
     use mintpool::rules::RulesEngine;
     use mintpool::run::start_p2p_services;
+    use mintpool::types::{PremintTypes, SimplePremint};
     use tower::ServiceExt;
 
     #[tokio::test]
@@ -26,6 +27,78 @@ mod api_test {
         assert_eq!(res.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_validate_premint() {
+        let mut config = Config::test_default();
+        config.api_port = 1112;
+
+        let router = make_test_router(&config).await;
+
+        // the empty token uri gets rejected
+        let premint = PremintTypes::Simple(SimplePremint::default());
+        let res = router
+            .clone()
+            .oneshot(
+                Request::post("/validate-premint")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&premint).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["accepted"], false);
+
+        let results = body["results"].as_array().unwrap();
+        let uri_length = results
+            .iter()
+            .find(|r| r["rule_name"] == "Metadata::token_uri_length")
+            .unwrap();
+        assert_eq!(uri_length["result"], "reject");
+        assert!(uri_length["duration_ms"].is_u64());
+
+        // a premint that passes every rule still isn't stored
+        let premint = PremintTypes::Simple(SimplePremint::new(
+            1,
+            7777777,
+            Default::default(),
+            1,
+            "ipfs://bafkreiaxsyptyhjcxyu6lccfhhmfhnaqmqkxggfpexv7kffhrceztqnxdq".to_string(),
+        ));
+        let res = router
+            .clone()
+            .oneshot(
+                Request::post("/validate-premint")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(serde_json::to_string(&premint).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["accepted"], true);
+
+        let res = router
+            .oneshot(Request::get("/list-all").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let premints: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(premints, serde_json::json!([]));
+    }
+
     async fn make_test_router(config: &Config) -> Router {
         let mut rules = RulesEngine::new(config);
         rules.add_default_rules();
@@ -6,18 +6,23 @@ use crate::common::mintpool_build::connect_all_to_first;
 use alloy::hex;
 use alloy::network::EthereumSigner;
 use alloy::node_bindings::Anvil;
-use alloy::primitives::{Bytes, TxKind, U256};
+use alloy::primitives::{address, Address, Bytes, TxKind, U256};
 use alloy::providers::{Provider, ProviderBuilder};
 use alloy::rpc::client::RpcClient;
 use alloy::rpc::types::eth::TransactionRequest;
 use alloy::signers::wallet::LocalWallet;
 use alloy::signers::Signer;
-use alloy::sol_types::{SolCall, SolValue};
+use alloy::sol_types::{SolCall, SolStruct, SolValue};
 use alloy::transports::{RpcError, TransportErrorKind};
+use mintpool::chain::view_contract_call;
+use mintpool::chain_list::CHAINS;
 use mintpool::config::{ChainInclusionMode, Config};
 use mintpool::controller::{ControllerCommands, DBQuery};
 use mintpool::premints::zora_premint::contract::IZoraPremintV2::MintArguments;
-use mintpool::premints::zora_premint::contract::{IZoraPremintV2, PREMINT_FACTORY_ADDR};
+use mintpool::premints::zora_premint::contract::{
+    IZoraPremintERC20V1, IZoraPremintV2, PREMINT_FACTORY_ADDR,
+};
+use mintpool::premints::zora_premint::erc20v1::ERC20V1;
 use mintpool::premints::zora_premint::v2::V2;
 use mintpool::rules::RulesEngine;
 use mintpool::run;
@@ -54,7 +59,7 @@ async fn test_zora_premint_v2_e2e() {
     let ctl = run::start_p2p_services(config.clone(), RulesEngine::new_with_default_rules(&config))
         .await
         .unwrap();
-    run::start_watch_chain::<V2>(&config, ctl.clone()).await;
+    run::start_watch_chain(&config, ctl.clone()).await.unwrap();
 
     // ============================================================================================
     // Publish a premint to the mintpool
@@ -176,6 +181,177 @@ async fn test_zora_premint_v2_e2e() {
     assert_eq!(all_premints.len(), 0);
 }
 
+/// Same lifecycle as `test_zora_premint_v2_e2e`, for an ERC20 premint signed by an anvil account.
+/// One node checks the chain, the other verifies the claim it gossips.
+#[test_log::test(tokio::test)]
+async fn test_zora_premint_erc20v1_e2e() {
+    // erc20 premints are newer than the block the v2 tests fork from
+    let fork_block = 19300000;
+    let anvil = Anvil::new()
+        .chain_id(7777777)
+        .fork_block_number(fork_block)
+        .fork("https://rpc.zora.energy")
+        .spawn();
+
+    let mut config1 = Config::test_default();
+    config1.secret = "0x04".to_string();
+    config1.peer_port = 5780;
+    config1.chain_inclusion_mode = ChainInclusionMode::Check;
+    config1.supported_premint_types = "zora_premint_erc20v1".to_string();
+
+    let mut config2 = Config::test_default();
+    config2.secret = "0x05".to_string();
+    config2.peer_port = 5781;
+    config2.chain_inclusion_mode = ChainInclusionMode::Verify;
+    config2.supported_premint_types = "zora_premint_erc20v1".to_string();
+
+    env::set_var("CHAIN_7777777_RPC_WSS", anvil.ws_endpoint());
+
+    let ctl1 = run::start_p2p_services(
+        config1.clone(),
+        RulesEngine::new_with_default_rules(&config1),
+    )
+    .await
+    .unwrap();
+    run::start_watch_chain(&config1, ctl1.clone())
+        .await
+        .unwrap();
+
+    let ctl2 = run::start_p2p_services(
+        config2.clone(),
+        RulesEngine::new_with_default_rules(&config2),
+    )
+    .await
+    .unwrap();
+
+    connect_all_to_first(vec![ctl1.clone(), ctl2.clone()]).await;
+    expect_n_connections(&ctl1, 1).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // ============================================================================================
+    // Sign an erc20 premint for a new collection owned by an anvil account
+    // ============================================================================================
+
+    let signer: LocalWallet = anvil.keys()[0].clone().into();
+    let signer = signer.with_chain_id(Some(7777777));
+
+    let collection = IZoraPremintERC20V1::ContractCreationConfig {
+        contractAdmin: signer.address(),
+        contractURI: "ipfs://bafkreicuxlqqgoo6fxlmijqvilckvwj6ey26yvzpwg73ybcltvvek2og6i"
+            .to_string(),
+        contractName: "ERC20 premint e2e".to_string(),
+    };
+
+    let rpc = CHAINS.get_rpc(7777777).await.unwrap();
+    let collection_address = view_contract_call(
+        IZoraPremintERC20V1::getContractAddressCall {
+            contractConfig: collection.clone(),
+        },
+        &rpc,
+        PREMINT_FACTORY_ADDR,
+    )
+    .await
+    .unwrap()
+    ._0;
+
+    let mut premint = ERC20V1 {
+        collection_address,
+        chain_id: 7777777,
+        collection,
+        ..Default::default()
+    };
+    premint.premint.uid = 1;
+    premint.premint.version = 1;
+    premint.premint.tokenConfig = IZoraPremintERC20V1::TokenCreationConfig {
+        tokenURI: "ipfs://bafkreia474gkk2ak5eeqstp43nqeiunqkkfeblctna3y54av7bt6uwehmq".to_string(),
+        maxSupply: U256::from(1000),
+        royaltyBPS: 500,
+        payoutRecipient: signer.address(),
+        createReferral: Address::ZERO,
+        erc20Minter: ERC20_MINTER_ADDR,
+        mintStart: 0,
+        mintDuration: 0,
+        maxTokensPerAddress: 0,
+        currency: address!("1111111111111111111111111111111111111111"),
+        pricePerToken: U256::from(1_000_000_000_000_000_000u128),
+    };
+    let hash = premint
+        .premint
+        .eip712_signing_hash(&premint.eip712_domain());
+    premint.signature = hex::encode_prefixed(signer.sign_hash(&hash).await.unwrap().as_bytes());
+
+    // ============================================================================================
+    // Publish the premint, both nodes should accept it with the default rules
+    // ============================================================================================
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    ctl1.send_command(ControllerCommands::Broadcast {
+        message: PremintTypes::ZoraERC20V1(premint.clone()),
+        channel: send,
+    })
+    .await
+    .unwrap();
+    recv.await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for ctl in [&ctl1, &ctl2] {
+        let all_premints = ctl.get_all_premints().await.unwrap();
+        assert_eq!(
+            all_premints,
+            vec![PremintTypes::ZoraERC20V1(premint.clone())]
+        );
+    }
+
+    // ============================================================================================
+    // bring premint onchain, without minting so no erc20 payment is needed
+    // ============================================================================================
+
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .signer(EthereumSigner::from(signer.clone()))
+        .on_client(RpcClient::new_http(anvil.endpoint_url()));
+
+    let calldata = IZoraPremintERC20V1::premintErc20V1Call {
+        contractConfig: premint.collection.clone(),
+        premintConfig: premint.premint.clone(),
+        signature: Bytes::from(hex::decode(&premint.signature).unwrap()),
+        quantityToMint: U256::ZERO,
+        mintArguments: IZoraPremintERC20V1::MintArguments {
+            mintRecipient: signer.address(),
+            mintComment: "".to_string(),
+            mintRewardsRecipients: vec![],
+        },
+        firstMinter: signer.address(),
+        signerContract: Address::ZERO,
+    };
+
+    let tx_request = TransactionRequest {
+        from: Some(signer.address()),
+        to: Some(TxKind::Call(PREMINT_FACTORY_ADDR)),
+        input: Some(Bytes::from(calldata.abi_encode())).into(),
+        chain_id: Some(7777777),
+        ..Default::default()
+    };
+
+    provider
+        .send_transaction(tx_request)
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // ============================================================================================
+    // node1 saw the event onchain, node2 verified the claim node1 sent
+    // ============================================================================================
+
+    for ctl in [&ctl1, &ctl2] {
+        let all_premints = ctl.get_all_premints().await.unwrap();
+        assert_eq!(all_premints.len(), 0);
+    }
+}
+
 // Spin up 2 nodes, one in check, one in verify, confirm that after the check node sees something onchain the verify node also will
 #[test_log::test(tokio::test)]
 async fn test_verify_e2e() {
@@ -208,7 +384,9 @@ async fn test_verify_e2e() {
     )
     .await
     .unwrap();
-    run::start_watch_chain::<V2>(&config1, ctl1.clone()).await;
+    run::start_watch_chain(&config1, ctl1.clone())
+        .await
+        .unwrap();
 
     let ctl2 = run::start_p2p_services(
         config2.clone(),
@@ -387,6 +565,9 @@ async fn test_verify_e2e() {
     assert_eq!(all_premints.len(), 0);
 }
 
+// zora's ERC20Minter, sales for erc20 premints are set up through it
+const ERC20_MINTER_ADDR: Address = address!("777777E8850d8D6d98De2B5f64fae401F96eFF31");
+
 const PREMINT_JSON: &str = r#"
 {
   "collection": {
@@ -4,6 +4,7 @@ use crate::common::{asserts, helpers, mintpool_build};
 use common::factories::Factory;
 use mintpool::controller::ControllerCommands;
 use mintpool::controller::ControllerCommands::Broadcast;
+use mintpool::events::PremintEvent;
 use mintpool::types::{PremintTypes, SimplePremint};
 use tokio::time;
 
@@ -87,6 +88,33 @@ async fn test_list_all_premints() {
     }
 }
 
+#[test_log::test(tokio::test)]
+// Nodes publish an event for every premint they accept, including ones received from peers
+async fn test_premint_events() {
+    let nodes = mintpool_build::gen_fully_connected_swarm(2320, 2).await;
+    let (first, nodes) = mintpool_build::split_first_rest(nodes).await;
+    let mut events = nodes[0].subscribe_events().await.unwrap();
+
+    let premint = PremintTypes::Simple(SimplePremint::build_default());
+    let (snd, _rcv) = tokio::sync::oneshot::channel();
+    first
+        .send_command(Broadcast {
+            message: premint.clone(),
+            channel: snd,
+        })
+        .await
+        .unwrap();
+
+    let event = time::timeout(time::Duration::from_secs(5), events.recv())
+        .await
+        .expect("no event received")
+        .unwrap();
+    match event {
+        PremintEvent::Accepted { premint: received } => assert_eq!(received, premint),
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
 #[test_log::test(tokio::test)]
 // Connections should not be able to exceed max_connections config
 async fn test_max_connections() {